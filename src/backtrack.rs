@@ -0,0 +1,213 @@
+use rand::{seq::SliceRandom, Rng};
+
+use crate::shape::Shape;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SolveStats {
+    pub guesses: usize,
+    pub backtracks: usize
+}
+
+//  Grid stored as the cells in row-major order, 0 being an empty cell.
+//  The used digits of every row, column and box are kept as bitmasks
+//  so finding the candidates of a cell is only a few bit operations.
+#[derive(Debug, Clone)]
+pub struct Backtracker {
+    shape: Shape,
+    cells: Vec<usize>,
+    rows: Vec<u32>,
+    cols: Vec<u32>,
+    blocks: Vec<u32>,
+    stats: SolveStats,
+    // A given breaks the rules, the grid has no solution
+    conflict: bool
+}
+
+impl Backtracker {
+    pub fn new(shape: Shape, cells: &[usize]) -> Backtracker {
+        let size = shape.size();
+
+        let mut backtracker = Backtracker {
+            shape,
+            cells: vec![0; shape.cells()],
+            rows: vec![0; size],
+            cols: vec![0; size],
+            blocks: vec![0; size],
+            stats: SolveStats::default(),
+            conflict: false
+        };
+
+        for (index, value) in cells.iter().copied().enumerate() {
+            if value == 0 {
+                continue;
+            }
+
+            match value <= size && backtracker.candidates(index) & (1 << value) != 0 {
+                false => backtracker.conflict = true,
+                true => backtracker.place(index, value)
+            }
+        }
+
+        backtracker
+    }
+
+    pub fn cells(&self) -> Vec<usize> {
+        self.cells.clone()
+    }
+
+    pub fn stats(&self) -> SolveStats {
+        self.stats
+    }
+
+    fn candidates(&self, index: usize) -> u32 {
+        let shape = self.shape;
+        let used = self.rows[shape.row_of(index)] | self.cols[shape.col_of(index)] | self.blocks[shape.box_of(index)];
+        !used & shape.full_mask()
+    }
+
+    fn place(&mut self, index: usize, value: usize) {
+        let bit = 1 << value;
+        self.cells[index] = value;
+        self.rows[self.shape.row_of(index)] |= bit;
+        self.cols[self.shape.col_of(index)] |= bit;
+        self.blocks[self.shape.box_of(index)] |= bit;
+    }
+
+    fn remove(&mut self, index: usize) {
+        let bit = !(1 << self.cells[index]);
+        self.cells[index] = 0;
+        self.rows[self.shape.row_of(index)] &= bit;
+        self.cols[self.shape.col_of(index)] &= bit;
+        self.blocks[self.shape.box_of(index)] &= bit;
+    }
+
+    // Pick the empty cell with the fewest candidates, None when the grid is full
+    fn next_cell(&self) -> Option<(usize, u32)> {
+        let mut best: Option<(usize, u32)> = None;
+
+        for index in 0..self.cells.len() {
+            if self.cells[index] != 0 {
+                continue;
+            }
+
+            let candidates = self.candidates(index);
+            let count = candidates.count_ones();
+
+            if best.is_none_or(|(_, best_candidates)| count < best_candidates.count_ones()) {
+                best = Some((index, candidates));
+                if count <= 1 {
+                    break;
+                }
+            }
+        }
+
+        best
+    }
+
+    pub fn solve(&mut self) -> bool {
+        if self.conflict {
+            return false
+        }
+
+        let (index, candidates) = match self.next_cell() {
+            None => return true,
+            Some(cell) => cell
+        };
+
+        let guessing = candidates.count_ones() > 1;
+
+        for value in digits(self.shape, candidates) {
+            if guessing {
+                self.stats.guesses += 1;
+            }
+
+            self.place(index, value);
+
+            if self.solve() {
+                return true;
+            }
+
+            self.remove(index);
+            self.stats.backtracks += 1;
+        }
+
+        false
+    }
+
+    // Count the solutions, stopping as soon as `limit` of them have been found
+    pub fn count_solutions(&mut self, limit: usize) -> usize {
+        let mut count = 0;
+
+        if limit > 0 && !self.conflict {
+            self.count(limit, &mut count);
+        }
+
+        count
+    }
+
+    fn count(&mut self, limit: usize, count: &mut usize) {
+        let (index, candidates) = match self.next_cell() {
+            None => {
+                *count += 1;
+                return
+            },
+            Some(cell) => cell
+        };
+
+        let guessing = candidates.count_ones() > 1;
+
+        for value in digits(self.shape, candidates) {
+            if guessing {
+                self.stats.guesses += 1;
+            }
+
+            self.place(index, value);
+            self.count(limit, count);
+            self.remove(index);
+
+            if *count >= limit {
+                return
+            }
+
+            self.stats.backtracks += 1;
+        }
+    }
+
+    // Same search as `solve`, but trying the candidates in a random order.
+    // Gives up after `max_backtracks`, a random search on the larger boards
+    // can get lost in a dead end that is quicker to start over.
+    pub fn solve_random<R: Rng>(&mut self, rng: &mut R, max_backtracks: usize) -> bool {
+        if self.conflict {
+            return false
+        }
+
+        let (index, candidates) = match self.next_cell() {
+            None => return true,
+            Some(cell) => cell
+        };
+
+        let mut values: Vec<usize> = digits(self.shape, candidates).collect();
+        values.shuffle(rng);
+
+        for value in values {
+            self.place(index, value);
+
+            if self.solve_random(rng, max_backtracks) {
+                return true;
+            }
+
+            self.remove(index);
+            self.stats.backtracks += 1;
+
+            if self.stats.backtracks > max_backtracks {
+                return false;
+            }
+        }
+
+        false
+    }
+}
+
+fn digits(shape: Shape, candidates: u32) -> impl Iterator<Item = usize> {
+    (1..=shape.size()).filter(move |value| candidates & (1 << value) != 0)
+}
@@ -1,10 +1,17 @@
+use serde::Serialize;
 
-#[derive(Debug, Hash, Clone, Copy)]
+#[derive(Debug, Hash, Clone, Copy, PartialEq, Eq, Serialize)]
 pub struct Cursor {
     pub row: usize,
     pub col: usize
 }
 
+impl Default for Cursor {
+    fn default() -> Self {
+        Cursor::new()
+    }
+}
+
 impl Cursor {
     pub fn new() -> Cursor {
         Cursor {
@@ -13,32 +20,32 @@ impl Cursor {
         }
     }
 
-    pub fn move_right(&mut self) {
+    // Rows and columns run from 1 to `size`
+    pub fn move_right(&mut self, size: usize) {
         self.col = match self.col {
-            1..=8 => self.col + 1,
-            9 => self.col,
-            _ => 9
+            col if col < size => col + 1,
+            _ => size
         }
     }
 
     pub fn move_left(&mut self) {
-        match self.col {
-            2..=9 => self.col = self.col - 1,
-            _ => (),
+        self.col = match self.col {
+            col if col > 1 => col - 1,
+            _ => 1
         }
     }
 
-    pub fn move_down(&mut self) {
-        match self.row {
-            1..=8 => self.row = self.row + 1,
-            _ => ()
+    pub fn move_down(&mut self, size: usize) {
+        self.row = match self.row {
+            row if row < size => row + 1,
+            _ => size
         }
     }
 
     pub fn move_up(&mut self) {
-        match self.row {
-            2..=9 => self.row = self.row - 1,
-            _ => ()
+        self.row = match self.row {
+            row if row > 1 => row - 1,
+            _ => 1
         }
     }
 
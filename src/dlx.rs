@@ -0,0 +1,292 @@
+use crate::shape::Shape;
+
+//  Sudoku as an exact cover problem solved with Knuth's Algorithm X on
+//  dancing links. Every row of the matrix places one digit in one cell and
+//  covers four columns: the cell, the digit in its row, in its column and in
+//  its box. A solution picks one row per cell covering every column once.
+
+const ROOT: usize = 0;
+
+#[derive(Debug, Clone)]
+pub struct Dlx {
+    shape: Shape,
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    column: Vec<usize>,
+    // Matrix row of every node, unused for the root and the column headers
+    row: Vec<usize>,
+    size: Vec<usize>,
+    cells: Vec<usize>,
+    // Rows picked by the search so far
+    picked: Vec<usize>,
+    // Rows tried by the search, it gives up once it passes `max_nodes`
+    nodes: usize,
+    max_nodes: usize,
+    // Givens that share a column with an earlier given or are not a digit
+    // of the board, no solution exists
+    conflict: bool
+}
+
+impl Dlx {
+    pub fn new(shape: Shape, cells: &[usize]) -> Dlx {
+        let columns = 4 * shape.cells();
+
+        let mut dlx = Dlx {
+            shape,
+            left: Vec::new(),
+            right: Vec::new(),
+            up: Vec::new(),
+            down: Vec::new(),
+            column: Vec::new(),
+            row: Vec::new(),
+            size: vec![0; columns + 1],
+            cells: cells.to_vec(),
+            picked: Vec::new(),
+            nodes: 0,
+            max_nodes: usize::MAX,
+            conflict: false
+        };
+
+        // The root followed by the column headers, linked in a circle
+        for node in 0..=columns {
+            dlx.left.push(if node == 0 { columns } else { node - 1 });
+            dlx.right.push(if node == columns { 0 } else { node + 1 });
+            dlx.up.push(node);
+            dlx.down.push(node);
+            dlx.column.push(node);
+            dlx.row.push(0);
+        }
+
+        for cell in 0..shape.cells() {
+            for value in 1..=shape.size() {
+                dlx.add_row(cell * shape.size() + value - 1, dlx.columns_of(cell, value));
+            }
+        }
+
+        // Givens are picked up front by covering their columns
+        for (cell, value) in cells.iter().copied().enumerate() {
+            if value == 0 {
+                continue;
+            }
+
+            if value > shape.size() {
+                dlx.conflict = true;
+                continue;
+            }
+
+            for column in dlx.columns_of(cell, value) {
+                if dlx.is_covered(column) {
+                    dlx.conflict = true;
+                } else {
+                    dlx.cover(column);
+                }
+            }
+        }
+
+        dlx
+    }
+
+    pub fn cells(&self) -> Vec<usize> {
+        self.cells.clone()
+    }
+
+    fn columns_of(&self, cell: usize, value: usize) -> [usize; 4] {
+        let shape = self.shape;
+        let (size, cells) = (shape.size(), shape.cells());
+        let digit = value - 1;
+
+        // Column headers start at node 1
+        [
+            1 + cell,
+            1 + cells + shape.row_of(cell) * size + digit,
+            1 + 2 * cells + shape.col_of(cell) * size + digit,
+            1 + 3 * cells + shape.box_of(cell) * size + digit
+        ]
+    }
+
+    fn add_row(&mut self, row: usize, columns: [usize; 4]) {
+        let first = self.left.len();
+
+        for (i, column) in columns.into_iter().enumerate() {
+            let node = first + i;
+
+            // Insert at the bottom of the column
+            self.up.push(self.up[column]);
+            self.down.push(column);
+            let bottom = self.up[column];
+            self.down[bottom] = node;
+            self.up[column] = node;
+
+            // Circular list of the nodes of this row
+            self.left.push(if i == 0 { first + 3 } else { node - 1 });
+            self.right.push(if i == 3 { first } else { node + 1 });
+
+            self.column.push(column);
+            self.row.push(row);
+            self.size[column] += 1;
+        }
+    }
+
+    fn is_covered(&self, column: usize) -> bool {
+        self.right[self.left[column]] != column
+    }
+
+    fn cover(&mut self, column: usize) {
+        let (left, right) = (self.left[column], self.right[column]);
+        self.right[left] = right;
+        self.left[right] = left;
+
+        let mut row = self.down[column];
+        while row != column {
+            let mut node = self.right[row];
+            while node != row {
+                let (up, down) = (self.up[node], self.down[node]);
+                self.down[up] = down;
+                self.up[down] = up;
+                self.size[self.column[node]] -= 1;
+                node = self.right[node];
+            }
+            row = self.down[row];
+        }
+    }
+
+    // Exact reverse of `cover`
+    fn uncover(&mut self, column: usize) {
+        let mut row = self.up[column];
+        while row != column {
+            let mut node = self.left[row];
+            while node != row {
+                let (up, down) = (self.up[node], self.down[node]);
+                self.down[up] = node;
+                self.up[down] = node;
+                self.size[self.column[node]] += 1;
+                node = self.left[node];
+            }
+            row = self.up[row];
+        }
+
+        let (left, right) = (self.left[column], self.right[column]);
+        self.right[left] = column;
+        self.left[right] = column;
+    }
+
+    // The uncovered column with the fewest rows, None when all are covered
+    fn next_column(&self) -> Option<usize> {
+        let mut best: Option<usize> = None;
+        let mut column = self.right[ROOT];
+
+        while column != ROOT {
+            if best.is_none_or(|best| self.size[column] < self.size[best]) {
+                best = Some(column);
+                if self.size[column] <= 1 {
+                    break;
+                }
+            }
+            column = self.right[column];
+        }
+
+        best
+    }
+
+    pub fn solve(&mut self) -> bool {
+        self.count_solutions(1) == 1
+    }
+
+    // Count the solutions, stopping as soon as `limit` of them have been
+    // found. The cells hold the first solution found.
+    pub fn count_solutions(&mut self, limit: usize) -> usize {
+        let mut count = 0;
+
+        if limit > 0 && !self.conflict {
+            self.search(limit, &mut count);
+        }
+
+        count
+    }
+
+    // Same as `count_solutions`, but gives up after trying `max_nodes` rows.
+    // The count is unknown then and None is returned. Sparse grids on the
+    // larger boards can take a very long time to count.
+    pub fn count_solutions_within(&mut self, limit: usize, max_nodes: usize) -> Option<usize> {
+        self.nodes = 0;
+        self.max_nodes = max_nodes;
+
+        let count = self.count_solutions(limit);
+        self.max_nodes = usize::MAX;
+
+        match self.nodes > max_nodes {
+            false => Some(count),
+            true => None
+        }
+    }
+
+    fn search(&mut self, limit: usize, count: &mut usize) {
+        let column = match self.next_column() {
+            None => {
+                *count += 1;
+                if *count == 1 {
+                    let size = self.shape.size();
+                    for row in self.picked.iter() {
+                        self.cells[row / size] = row % size + 1;
+                    }
+                }
+                return
+            },
+            Some(column) => column
+        };
+
+        self.cover(column);
+
+        let mut row = self.down[column];
+        while row != column && *count < limit && self.nodes <= self.max_nodes {
+            self.nodes += 1;
+            self.picked.push(self.row[row]);
+
+            let mut node = self.right[row];
+            while node != row {
+                self.cover(self.column[node]);
+                node = self.right[node];
+            }
+
+            self.search(limit, count);
+
+            let mut node = self.left[row];
+            while node != row {
+                self.uncover(self.column[node]);
+                node = self.left[node];
+            }
+
+            self.picked.pop();
+            row = self.down[row];
+        }
+
+        self.uncover(column);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_stops_at_limit() {
+        // The empty 4x4 board has 288 solutions
+        let empty = vec![0; 16];
+        let shape = Shape::new(2, 2).unwrap();
+
+        assert_eq!(Dlx::new(shape, &empty).count_solutions(1000), 288);
+        assert_eq!(Dlx::new(shape, &empty).count_solutions(10), 10);
+        assert_eq!(Dlx::new(shape, &empty).count_solutions(0), 0);
+    }
+
+    #[test]
+    fn count_within_gives_up_on_budget() {
+        let empty = vec![0; 81];
+
+        // A single solution already takes a row for each of the 81 cells
+        assert_eq!(Dlx::new(Shape::classic(), &empty).count_solutions_within(2, 50), None);
+        assert_eq!(Dlx::new(Shape::classic(), &empty).count_solutions_within(2, 10_000), Some(2));
+    }
+}
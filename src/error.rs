@@ -0,0 +1,61 @@
+use std::{error::Error, fmt::Display};
+
+use crate::generator;
+use crate::model::Conflict;
+use crate::shape::{self, Shape};
+use crate::technique::Difficulty;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SudokoError {
+    // Coordinates outside the grid
+    OutOfRange { row: usize, col: usize },
+    BlockOutOfRange(usize),
+    InvalidShape { box_rows: usize, box_cols: usize },
+    UnsupportedShape(Shape),
+    // Too large to check every clue, see `MAX_MINIMAL_SIZE`
+    MinimalUnsupported(Shape),
+    Locked { row: usize, col: usize },
+    InvalidDigit(usize),
+    // A puzzle line with a number of cells that makes no board
+    InvalidLength(usize),
+    InvalidSymbol(char),
+    // The same digit twice in a house
+    Conflict(Conflict),
+    NoSolution,
+    // Only a puzzle with a single solution can be rated
+    MultipleSolutions,
+    // The solver gave up without proving there is no solution
+    Unsolved { solver: &'static str },
+    GenerationFailed { difficulty: Option<Difficulty>, attempts: u64 }
+}
+
+impl Display for SudokoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SudokoError::OutOfRange { row, col } => write!(f, "Row {row}, column {col} is outside the grid"),
+            SudokoError::BlockOutOfRange(block) => write!(f, "Block {block} is outside the grid"),
+            SudokoError::InvalidShape { box_rows, box_cols } => write!(f,
+                "Boxes of {box_rows}x{box_cols} cells do not make a board of 2 to {} digits", Shape::MAX_SIZE),
+            SudokoError::UnsupportedShape(shape) => write!(f, "Puzzles are only rated on 9x9 boards, not {shape}"),
+            SudokoError::MinimalUnsupported(shape) => write!(f,
+                "Minimal puzzles only exist up to {0}x{0} boards, not {shape}", generator::MAX_MINIMAL_SIZE),
+            SudokoError::Locked { row, col } => write!(f, "Value at row {row}, column {col} is locked!"),
+            SudokoError::InvalidDigit(value) => write!(f, "Value {value} is not a digit of this board"),
+            SudokoError::InvalidLength(cells) => write!(f, "A puzzle needs {} cells, found {cells}",
+                Shape::all().map(|shape| shape.cells().to_string()).join(", ")),
+            SudokoError::InvalidSymbol(symbol) => write!(f, "'{symbol}' is not a digit"),
+            SudokoError::Conflict(conflict) => write!(f,
+                "Value '{}' is already present in {}, at r{}c{} and r{}c{}", shape::symbol(conflict.value), conflict.house,
+                conflict.cells[0].row, conflict.cells[0].col, conflict.cells[1].row, conflict.cells[1].col),
+            SudokoError::NoSolution => write!(f, "Puzzle has no solution!"),
+            SudokoError::MultipleSolutions => write!(f, "Puzzle has more than one solution"),
+            SudokoError::Unsolved { solver } => write!(f, "The {solver} solver could not finish the puzzle"),
+            SudokoError::GenerationFailed { difficulty, attempts } => match difficulty {
+                None => write!(f, "Could not generate a puzzle"),
+                Some(difficulty) => write!(f, "Could not generate a {difficulty} puzzle in {attempts} attempts")
+            }
+        }
+    }
+}
+
+impl Error for SudokoError {}
@@ -0,0 +1,189 @@
+use std::{collections::BTreeSet, fmt::Display};
+
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::backtrack::Backtracker;
+use crate::dlx::Dlx;
+use crate::shape::Shape;
+use crate::technique::Difficulty;
+
+// Rows the uniqueness check may try, far more than a classic puzzle needs
+const MAX_COUNT_NODES: usize = 50_000;
+
+// Largest board on which every clue can be checked exactly, on larger ones a
+// count can take minutes
+pub const MAX_MINIMAL_SIZE: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Symmetry {
+    #[default]
+    None,
+    Rotational,
+    Horizontal,
+    Vertical,
+    Diagonal,
+    Dihedral
+}
+
+impl Symmetry {
+    pub fn all() -> [Symmetry; 6] {
+        [Symmetry::None, Symmetry::Rotational, Symmetry::Horizontal,
+         Symmetry::Vertical, Symmetry::Diagonal, Symmetry::Dihedral]
+    }
+
+    // Every cell that must be a clue together with the given cell, on a
+    // board with `size` rows and columns
+    pub fn orbit(&self, size: usize, cell: usize) -> BTreeSet<usize> {
+        let (row, col) = (cell / size, cell % size);
+        let last = size - 1;
+
+        let images = match self {
+            Symmetry::None => vec![(row, col)],
+            Symmetry::Rotational => vec![(row, col), (last - row, last - col)],
+            Symmetry::Horizontal => vec![(row, col), (last - row, col)],
+            Symmetry::Vertical => vec![(row, col), (row, last - col)],
+            Symmetry::Diagonal => vec![(row, col), (col, row)],
+            Symmetry::Dihedral => vec![
+                (row, col), (col, last - row), (last - row, last - col), (last - col, row),
+                (last - row, col), (row, last - col), (col, row), (last - col, last - row)
+            ]
+        };
+
+        images.into_iter().map(|(row, col)| row * size + col).collect()
+    }
+}
+
+impl Display for Symmetry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Symmetry::None => write!(f, "None"),
+            Symmetry::Rotational => write!(f, "Rotational"),
+            Symmetry::Horizontal => write!(f, "Horizontal mirror"),
+            Symmetry::Vertical => write!(f, "Vertical mirror"),
+            Symmetry::Diagonal => write!(f, "Diagonal"),
+            Symmetry::Dihedral => write!(f, "Dihedral")
+        }
+    }
+}
+
+//  Options used to generate a puzzle. A generated puzzle keeps its options
+//  with the seed filled in, so it can be generated again from them.
+//  A minimal puzzle has every clue removed that can be removed one at a
+//  time, this can break the symmetry of the clues. Only boards up to
+//  `MAX_MINIMAL_SIZE` can be made minimal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct GeneratorOptions {
+    pub shape: Shape,
+    pub seed: Option<u64>,
+    pub difficulty: Option<Difficulty>,
+    pub symmetry: Symmetry,
+    pub minimal: bool
+}
+
+//  ChaCha8 is used as it produces the same stream for a seed on every
+//  platform, so a seed always gives the same puzzle.
+pub struct Generator {
+    rng: ChaCha8Rng,
+    seed: u64,
+    shape: Shape
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Generator::new()
+    }
+}
+
+impl Generator {
+    pub fn new() -> Generator {
+        Generator::from_seed(rand::random())
+    }
+
+    pub fn from_seed(seed: u64) -> Generator {
+        Generator {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            seed,
+            shape: Shape::classic()
+        }
+    }
+
+    pub fn with_shape(self, shape: Shape) -> Generator {
+        Generator { shape, ..self }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // A random, completely filled and valid grid
+    pub fn full_grid(&mut self) -> Vec<usize> {
+        // An empty grid can always be completed, a search that gets stuck
+        // is started over
+        loop {
+            let mut backtracker = Backtracker::new(self.shape, &vec![0; self.shape.cells()]);
+
+            if backtracker.solve_random(&mut self.rng, self.shape.cells()) {
+                return backtracker.cells()
+            }
+        }
+    }
+
+    // Remove clues from a full grid in random order, only keeping the
+    // removal when the puzzle still has exactly one solution. Clues are
+    // removed a whole symmetry orbit at a time.
+    pub fn puzzle(&mut self, symmetry: Symmetry) -> Vec<usize> {
+        let mut grid = self.full_grid();
+
+        let mut order: Vec<usize> = (0..grid.len()).collect();
+        order.shuffle(&mut self.rng);
+
+        for index in order {
+            if grid[index] == 0 {
+                continue;
+            }
+
+            let orbit = symmetry.orbit(self.shape.size(), index);
+            let removed: Vec<(usize, usize)> = orbit.iter().map(|cell| (*cell, grid[*cell])).collect();
+
+            for cell in &orbit {
+                grid[*cell] = 0;
+            }
+
+            if !self.is_unique(&grid) {
+                for (cell, value) in removed {
+                    grid[cell] = value;
+                }
+            }
+        }
+
+        grid
+    }
+
+    // Remove every clue that can be removed on its own. Removing clues can
+    // never make another clue removable, so a single pass is enough. Every
+    // count is exact, unlike the counts of `puzzle` that may give up.
+    pub fn minimize(&mut self, grid: Vec<usize>) -> Vec<usize> {
+        let mut grid = grid;
+
+        let mut order: Vec<usize> = (0..grid.len()).filter(|index| grid[*index] != 0).collect();
+        order.shuffle(&mut self.rng);
+
+        for index in order {
+            let value = grid[index];
+            grid[index] = 0;
+
+            if Dlx::new(self.shape, &grid).count_solutions(2) != 1 {
+                grid[index] = value;
+            }
+        }
+
+        grid
+    }
+
+    // Counting can take very long on the larger boards, when the search
+    // gives up the grid is taken as not unique so the clue stays
+    fn is_unique(&self, grid: &[usize]) -> bool {
+        Dlx::new(self.shape, grid).count_solutions_within(2, MAX_COUNT_NODES) == Some(1)
+    }
+}
@@ -1,2 +1,11 @@
 pub mod model;
-pub mod cursor;
\ No newline at end of file
+pub mod shape;
+pub mod error;
+pub mod cursor;
+pub mod view;
+pub mod backtrack;
+pub mod dlx;
+pub mod solver;
+pub mod generator;
+pub mod technique;
+pub mod rating;
\ No newline at end of file
@@ -1,39 +1,79 @@
+use std::error::Error;
+
 use colored::Colorize;
+use sudoko::error::SudokoError;
 use sudoko::model::{Sudoko, SudokoValue};
-use sudoko::cursor::{self, Cursor};
+use sudoko::cursor::Cursor;
+use sudoko::generator::{GeneratorOptions, Symmetry};
+use sudoko::rating;
+use sudoko::shape::{self, Shape};
+use sudoko::solver::{self, Solver};
+use sudoko::technique::{Difficulty, Hint, StepOutcome};
+use sudoko::view::SudokoView;
 use console::Term;
-use std::{thread, time::Duration};
+
+// Larger boards take seconds to generate, they can still be entered with `p`
+// or generated with `sudoko generate`
+const MAX_GENERATED_SIZE: usize = 12;
 
 fn main() {
+    // Without arguments the interactive game is started
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        if let Err(e) = run_command(&args) {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let mut sudoko = Sudoko::new();
     let stdout = Term::buffered_stdout();
     let mut cursor = Cursor::new();
-
-    /*sudoko.set_value(1, 1, SudokoValue::One(false)).unwrap();
-    sudoko.set_value(1, 2, SudokoValue::Two(false)).unwrap();
-    sudoko.set_value(2, 1, SudokoValue::Three(false)).unwrap();
-    sudoko.set_value(3, 3, SudokoValue::Four(false)).unwrap();
-    sudoko.set_value(1, 5, SudokoValue::Five(false)).unwrap();
-    sudoko.set_value(2, 4, SudokoValue::Six(false)).unwrap();
-    sudoko.set_value(3, 6, SudokoValue::Seven(false)).unwrap();
-    sudoko.set_value(1, 9, SudokoValue::Eight(false)).unwrap();
-    sudoko.set_value(6, 9, SudokoValue::Nine(false)).unwrap();*/
+    let mut message: Option<String> = None;
+    let mut hint_text: Option<String> = None;
+    let mut hints = HintState::default();
+    let mut statistics = Statistics::default();
+    let mut options = GeneratorOptions::default();
+    let mut solver = solver::default_for(sudoko.shape());
 
     'main_loop: loop {
 
         clearscreen::clear().unwrap();
 
-        sudoko.select_value(cursor.row, cursor.col).unwrap();
-
-        println!("{sudoko}");
-        println!("[←↑↓→] move cursor, [1-9] enter value");
+        println!("{}", SudokoView::new(&sudoko, Some(cursor)));
+        if let Some(text) = hint_text.take() {
+            println!("{}", text.bright_yellow());
+        }
+        if let Some(generated) = sudoko.generator_options() {
+            if let Some(seed) = generated.seed {
+                match generated.minimal {
+                    false => println!("Seed: {seed}, symmetry: {}", generated.symmetry),
+                    true => println!("Seed: {seed}, symmetry: {}, minimal", generated.symmetry)
+                }
+            }
+        }
+        match sudoko.shape().size() {
+            size @ ..=9 => println!("[←↑↓→] move cursor, [1-{size}] enter value"),
+            size => println!("[←↑↓→] move cursor, [1-9, A-{}] enter value", shape::symbol(size))
+        }
         println!("[backspace] remove value");
-        println!("[n] generate a new puzzle");
+        println!("[n] generate a new puzzle [g] generate from a seed [p] enter a puzzle line");
+        match options.difficulty {
+            None => println!("[d] change difficulty: Any"),
+            Some(difficulty) => println!("[d] change difficulty: {difficulty}")
+        }
+        println!("[y] change symmetry: {}", options.symmetry);
+        println!("[z] change board size: {}", options.shape);
+        match options.minimal {
+            false => println!("[m] minimal puzzles: off"),
+            true => println!("[m] minimal puzzles: on")
+        }
         println!("[l] lock values [u] unlock values");
-        println!("[h] help / solve 1 step");
-        println!("[s] solve the puzzle");
+        println!("[h] hint, press again for more help / solve 1 step");
+        println!("[s] solve the puzzle [b] change solver: {}", solver.name());
         println!("[q/esc] quit");
-        if let Some(possible_values) = sudoko.find_possible_values(cursor.row, cursor.col).unwrap() {
+        if let Some(possible_values) = sudoko.get_candidates(cursor.row, cursor.col).unwrap() {
             println!("Possible values: ");
             for value in possible_values {
                 print!("{value} ")
@@ -42,10 +82,16 @@ fn main() {
         }
         
 
-        if let Err(e) = sudoko.validate() {
-            println!("Validation error: {}", e.as_str().bright_red());
+        for conflict in sudoko.conflicts() {
+            println!("Conflict: {}", conflict.to_string().bright_red());
+        }
+
+        if let Some(text) = message.take() {
+            println!("{text}");
         }
 
+        println!("Hints used: {} ({} steps revealed)", statistics.hints, statistics.revealed);
+
         if let Ok(key) = stdout.read_key() {
             match key {
                 console::Key::UnknownEscSeq(_) => break 'main_loop,
@@ -53,80 +99,157 @@ fn main() {
                     cursor.move_left();
                 },
                 console::Key::ArrowRight => {
-                    cursor.move_right();
+                    cursor.move_right(sudoko.shape().size());
                 },
                 console::Key::ArrowUp => {
                     cursor.move_up();
                 },
                 console::Key::ArrowDown => {
-                    cursor.move_down();
+                    cursor.move_down(sudoko.shape().size());
                 },
                 console::Key::Escape => break 'main_loop,
                 console::Key::Backspace => {
-                    match sudoko.set_value(cursor.row, cursor.col, SudokoValue::Empty(true)) {
-                        Err(e) => println!("{e}"),
-                        Ok(_) => ()
+                    if let Err(e) = sudoko.set_value(cursor.row, cursor.col, SudokoValue::Empty) {
+                        message = Some(e.to_string())
                     }
                 },
                 console::Key::Char(character) => {
                     match character {
                         'q' => break 'main_loop,
-                        'h' => cursor = sudoko.solve_step().unwrap(),
-                        's' => sudoko.solve().unwrap(),
-                        'l' => sudoko.lock().unwrap(),
-                        'u' => sudoko.unlock(),
-                        '1' => {
-                            match sudoko.set_value(cursor.row, cursor.col, SudokoValue::One(true)) {
-                                Err(e) => println!("{e}"),
-                                Ok(_) => ()
+                        'n' => {
+                            match generate(options) {
+                                Err(e) => message = Some(e.to_string()),
+                                Ok((puzzle, text)) => {
+                                    message = Some(text);
+                                    load(puzzle, &mut sudoko, &mut cursor, &mut statistics, &mut hints, &mut solver)
+                                }
                             }
                         },
-                        '2' => {
-                            match sudoko.set_value(cursor.row, cursor.col, SudokoValue::Two(true)) {
-                                Err(e) => println!("{e}"),
-                                Ok(_) => ()
+                        'd' => {
+                            // Cycle through Any and then each difficulty in order
+                            options.difficulty = match options.difficulty {
+                                None => Some(Difficulty::Easy),
+                                Some(current) => Difficulty::all().into_iter().find(|next| *next > current)
                             }
                         },
-                        '3' => {
-                            match sudoko.set_value(cursor.row, cursor.col, SudokoValue::Three(true)) {
-                                Err(e) => println!("{e}"),
-                                Ok(_) => ()
-                            }
+                        'm' => options.minimal = !options.minimal,
+                        'y' => {
+                            let all = Symmetry::all();
+                            let current = all.iter().position(|symmetry| *symmetry == options.symmetry).unwrap_or(0);
+                            options.symmetry = all[(current + 1) % all.len()];
                         },
-                        '4' => {
-                            match sudoko.set_value(cursor.row, cursor.col, SudokoValue::Four(true)) {
-                                Err(e) => println!("{e}"),
-                                Ok(_) => ()
-                            }
+                        'z' => {
+                            let all: Vec<Shape> = Shape::all().into_iter().filter(|shape| shape.size() <= MAX_GENERATED_SIZE).collect();
+                            let current = all.iter().position(|shape| *shape == options.shape).unwrap_or(0);
+                            options.shape = all[(current + 1) % all.len()];
                         },
-                        '5' => {
-                            match sudoko.set_value(cursor.row, cursor.col, SudokoValue::Five(true)) {
-                                Err(e) => println!("{e}"),
-                                Ok(_) => ()
+                        'g' => {
+                            println!("Enter seed: ");
+                            if let Ok(line) = stdout.read_line() {
+                                match line.trim().parse::<u64>() {
+                                    Err(_) => message = Some(format!("'{}' is not a valid seed", line.trim())),
+                                    Ok(seed) => match generate(GeneratorOptions { seed: Some(seed), ..options }) {
+                                        Err(e) => message = Some(e.to_string()),
+                                        Ok((puzzle, text)) => {
+                                            message = Some(text);
+                                            load(puzzle, &mut sudoko, &mut cursor, &mut statistics, &mut hints, &mut solver)
+                                        }
+                                    }
+                                }
                             }
                         },
-                        '6' => {
-                            match sudoko.set_value(cursor.row, cursor.col, SudokoValue::Six(true)) {
-                                Err(e) => println!("{e}"),
-                                Ok(_) => ()
+                        'p' => {
+                            println!("Enter puzzle: ");
+                            if let Ok(line) = stdout.read_line() {
+                                match line.parse::<Sudoko>() {
+                                    Err(e) => message = Some(e.to_string()),
+                                    Ok(puzzle) => load(puzzle, &mut sudoko, &mut cursor, &mut statistics, &mut hints, &mut solver)
+                                }
                             }
                         },
-                        '7' => {
-                            match sudoko.set_value(cursor.row, cursor.col, SudokoValue::Seven(true)) {
-                                Err(e) => println!("{e}"),
-                                Ok(_) => ()
+                        'h' => {
+                            match sudoko.next_hint() {
+                                Err(e) => message = Some(e.to_string()),
+                                Ok(StepOutcome::Solved) => message = Some("The puzzle is already solved".to_owned()),
+                                Ok(StepOutcome::Stuck) => {
+                                    hints.pending = None;
+                                    hints.level = 0;
+
+                                    // Ask first, the second press falls back to the solution
+                                    match hints.offered_guess {
+                                        false => {
+                                            hint_text = Some("Stuck: no technique finds the next step. Press [h] again to let the solver fill in a cell".to_owned());
+                                            hints.offered_guess = true;
+                                        },
+                                        true => {
+                                            match sudoko.solve_step_with(solver.as_mut()) {
+                                                Err(e) => message = Some(e.to_string()),
+                                                Ok(StepOutcome::Deduced(step)) => {
+                                                    cursor = step.cursor();
+                                                    hint_text = Some(step.explanation);
+                                                    statistics.hints += 1;
+                                                    statistics.revealed += 1;
+                                                },
+                                                Ok(_) => message = Some(format!("The {} solver is stuck too, pick another one with [b]", solver.name()))
+                                            }
+                                            hints.offered_guess = false;
+                                        }
+                                    }
+                                },
+                                Ok(StepOutcome::Deduced(next)) => {
+                                    hints.offered_guess = false;
+
+                                    // Start over when the grid changed and the next step is a different one
+                                    if hints.pending.as_ref() != Some(&next) {
+                                        hints.level = 0;
+                                    }
+                                    hints.level += 1;
+                                    statistics.hints += 1;
+
+                                    match hints.level {
+                                        1 => hint_text = Some(next.area()),
+                                        2 => hint_text = Some(next.outline()),
+                                        _ => {
+                                            match sudoko.solve_step() {
+                                                Err(e) => message = Some(e.to_string()),
+                                                Ok(StepOutcome::Deduced(step)) => {
+                                                    cursor = step.cursor();
+                                                    hint_text = Some(step.explanation);
+                                                },
+                                                Ok(_) => ()
+                                            }
+                                            statistics.revealed += 1;
+                                            hints.level = 0;
+                                        }
+                                    }
+
+                                    hints.pending = match hints.level {
+                                        0 => None,
+                                        _ => Some(next)
+                                    };
+                                }
                             }
                         },
-                        '8' => {
-                            match sudoko.set_value(cursor.row, cursor.col, SudokoValue::Eight(true)) {
-                                Err(e) => println!("{e}"),
-                                Ok(_) => ()
+                        's' => {
+                            message = match sudoko.solve_with(solver.as_mut()) {
+                                Err(e) => Some(e.to_string()),
+                                Ok(()) => match solver.stats() {
+                                    None => Some(format!("Solved with the {} solver", solver.name())),
+                                    Some(stats) => Some(format!("Solved with {} guesses and {} backtracks", stats.guesses, stats.backtracks))
+                                }
                             }
                         },
-                        '9' => {
-                            match sudoko.set_value(cursor.row, cursor.col, SudokoValue::Nine(true)) {
-                                Err(e) => println!("{e}"),
-                                Ok(_) => ()
+                        'b' => {
+                            let current = solver::SOLVERS.iter().position(|name| *name == solver.name()).unwrap_or(0);
+                            solver = solver::by_name(solver::SOLVERS[(current + 1) % solver::SOLVERS.len()]).unwrap();
+                        },
+                        'l' => sudoko.lock().unwrap(),
+                        'u' => sudoko.unlock(),
+                        '1'..='9' | 'A'..='P' => {
+                            // Digits above 9 are entered as the letters A to P
+                            let value = SudokoValue::new(shape::value_of(character));
+                            if let Err(e) = value.and_then(|value| sudoko.set_value(cursor.row, cursor.col, value)) {
+                                message = Some(e.to_string())
                             }
                         },
                         _ => ()
@@ -141,3 +264,142 @@ fn main() {
     //let value = sudoko.get_value(2, 5).unwrap();
 
 }
+
+//  sudoko path <puzzle> [--json]
+//  sudoko solve <puzzle> [--solver <name>]
+//  sudoko count <puzzle> [--solver <name>]
+//  sudoko check <puzzle>
+//  sudoko generate [<size>] [--seed <seed>]
+//  The puzzle is given as one line of cells in row order with 0 or . for
+//  empty cells, 81 of them for the classic board. `path` prints every step
+//  the techniques take to solve it, `generate` prints a puzzle line.
+fn run_command(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let usage = format!("Usage: sudoko path <puzzle> [--json] | solve <puzzle> [--solver <name>] | count <puzzle> [--solver <name>] | check <puzzle> | generate [<size>] [--seed <seed>]\nSolvers: {}\nSizes: {}",
+        solver::SOLVERS.join(", "), Shape::all().map(|shape| shape.size().to_string()).join(", "));
+
+    let command = args.first().map(|arg| arg.as_str());
+
+    // The first argument that is neither a flag nor the value of one
+    let argument = args.iter().enumerate().skip(1)
+        .find(|(index, arg)| !arg.starts_with("--") && !["--solver", "--seed"].contains(&args[index - 1].as_str()))
+        .map(|(_, arg)| arg.as_str());
+    let flag = |name: &str| -> Result<Option<&String>, String> {
+        match args.iter().position(|arg| arg == name) {
+            None => Ok(None),
+            Some(index) => args.get(index + 1).map(Some).ok_or(usage.clone())
+        }
+    };
+    let puzzle = || -> Result<Sudoko, Box<dyn Error>> {
+        Ok(argument.ok_or(usage.clone())?.parse::<Sudoko>()?)
+    };
+    let solver = |shape: Shape| -> Result<Box<dyn Solver>, Box<dyn Error>> {
+        match flag("--solver")? {
+            None => Ok(solver::default_for(shape)),
+            Some(name) => Ok(solver::by_name(name).ok_or(format!("Unknown solver '{name}'\n{usage}"))?)
+        }
+    };
+
+    match command {
+        Some("path") => {
+            let json = args.iter().any(|arg| arg == "--json");
+
+            let path = puzzle()?.solve_path()?;
+            match json {
+                false => println!("{path}"),
+                true => println!("{}", serde_json::to_string_pretty(&path).map_err(|e| e.to_string())?)
+            }
+        },
+        Some("solve") => {
+            let mut sudoko = puzzle()?;
+            sudoko.solve_with(solver(sudoko.shape())?.as_mut())?;
+            println!("{}", to_line(&sudoko)?);
+        },
+        Some("count") => {
+            // Stop at 1000, counting an empty grid would never finish
+            let sudoko = puzzle()?;
+            println!("{}", sudoko.count_solutions_with(solver(sudoko.shape())?.as_mut(), 1000)?);
+        },
+        Some("check") => {
+            let conflicts = puzzle()?.conflicts();
+            if conflicts.is_empty() {
+                println!("No conflicts");
+            }
+            for conflict in conflicts {
+                println!("{conflict}");
+            }
+        },
+        Some("generate") => {
+            let shape = match argument {
+                None => Shape::classic(),
+                Some(size) => Shape::all().into_iter()
+                    .find(|shape| shape.size().to_string() == size)
+                    .ok_or(format!("No board of size '{size}'\n{usage}"))?
+            };
+            let seed = match flag("--seed")? {
+                None => None,
+                Some(seed) => Some(seed.parse::<u64>().map_err(|_| format!("'{seed}' is not a valid seed"))?)
+            };
+
+            let sudoko = Sudoko::generate_with(GeneratorOptions { shape, seed, ..GeneratorOptions::default() })?;
+            println!("{}", to_line(&sudoko)?);
+        },
+        _ => return Err(usage.into())
+    }
+
+    Ok(())
+}
+
+fn to_line(sudoko: &Sudoko) -> Result<String, SudokoError> {
+    let mut line = String::new();
+
+    for row in 1..=sudoko.shape().size() {
+        for col in 1..=sudoko.shape().size() {
+            match sudoko.get_value(row, col)?.to_value() {
+                None => line.push('.'),
+                Some(value) => line.push(shape::symbol(value))
+            }
+        }
+    }
+
+    Ok(line)
+}
+
+//  Statistics of the current game, every hint level shown counts as a hint
+#[derive(Debug, Default)]
+struct Statistics {
+    hints: usize,
+    revealed: usize
+}
+
+//  The hint being revealed one level at a time, and whether the solver was
+//  offered to fill in a cell after the techniques got stuck
+#[derive(Debug, Default)]
+struct HintState {
+    pending: Option<Hint>,
+    level: usize,
+    offered_guess: bool
+}
+
+fn generate(options: GeneratorOptions) -> Result<(Sudoko, String), SudokoError> {
+    let puzzle = Sudoko::generate_with(options)?;
+
+    let text = match rating::rate(&puzzle) {
+        Err(SudokoError::UnsupportedShape(shape)) => format!("Generated a {shape} puzzle with {} clues", puzzle.clues()),
+        Err(e) => e.to_string(),
+        Ok(rating) => format!("Generated a puzzle with {} clues, rated {rating}", puzzle.clues())
+    };
+
+    Ok((puzzle, text))
+}
+
+// A new puzzle starts a new game, nothing of the previous one is kept. A
+// board of another size also gets the solver that finishes on it.
+fn load(puzzle: Sudoko, sudoko: &mut Sudoko, cursor: &mut Cursor, statistics: &mut Statistics, hints: &mut HintState, solver: &mut Box<dyn Solver>) {
+    *statistics = Statistics::default();
+    *hints = HintState::default();
+    if puzzle.shape() != sudoko.shape() {
+        *cursor = Cursor::new();
+        *solver = solver::default_for(puzzle.shape());
+    }
+    *sudoko = puzzle
+}
@@ -1,632 +1,631 @@
-use std::{collections::{hash_map::Values, HashMap, HashSet}, fmt::Display, io::Empty, thread, time::Duration};
+use std::{collections::{HashMap, HashSet}, fmt::Display, str::FromStr};
 
-use colored::Colorize;
-use itertools::Itertools;
+use colored::{Color, Colorize};
 
+use crate::backtrack::SolveStats;
 use crate::cursor::Cursor;
-
-#[allow(dead_code)]
+use crate::dlx::Dlx;
+use crate::error::SudokoError;
+use crate::generator::{self, Generator, GeneratorOptions};
+use crate::rating;
+use crate::shape::{self, Shape};
+use crate::solver::{self, DlxSolver, Solver, TechniqueSolver};
+use crate::technique::{Candidates, Deduction, Difficulty, Hint, House, SolvePath, StepOutcome};
+use crate::view::SudokoView;
+
+const MAX_GENERATE_ATTEMPTS: u64 = 1000;
+
+// Two cells holding the same value in a house
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct SudokoIndex {
-    row: usize,
-    col: usize,
-    row_block: usize,
-    col_block: usize,
-    internal_row: usize,
-    internal_col: usize,
-    index_block: usize
+pub struct Conflict {
+    pub value: usize,
+    pub house: House,
+    pub cells: [Cursor; 2]
 }
 
-#[allow(dead_code)]
-//  Storing the data in an fixed length array
+impl Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} in {} at r{}c{} and r{}c{}", shape::symbol(self.value), self.house,
+            self.cells[0].row, self.cells[0].col, self.cells[1].row, self.cells[1].col)
+    }
+}
+
+//  Storing the values of the cells in row-major order, the shape of the
+//  board is chosen at construction time. Blocks are numbered the same way,
+//  on the classic board:
 //  0 - 1 - 2
 //  3 - 4 - 5
 //  6 - 7 - 8
 // 
 #[derive(Debug, Clone)]
 pub struct Sudoko {
-    data: [SudokoBlock; 9],
-    selected: Option<SudokoIndex>,
-    lock: HashSet<(usize, usize)>
+    shape: Shape,
+    data: Vec<SudokoValue>,
+    lock: HashSet<(usize, usize)>,
+    // Digits used in every row, column and block, bit 1 to `size` is set
+    // when the digit is present. Kept up to date by `set_value`.
+    rows: Vec<u32>,
+    cols: Vec<u32>,
+    blocks: Vec<u32>,
+    candidates: Candidates,
+    generated: Option<GeneratorOptions>
+}
+
+impl Default for Sudoko {
+    fn default() -> Self {
+        Sudoko::new()
+    }
 }
 
 impl Sudoko {
     pub fn new() -> Sudoko {
+        Sudoko::with_shape(Shape::classic())
+    }
+
+    pub fn with_shape(shape: Shape) -> Sudoko {
         Sudoko {
-            data: [SudokoBlock::new(); 9],
-            selected: None,
-            lock: HashSet::new()
+            shape,
+            data: vec![SudokoValue::Empty; shape.cells()],
+            lock: HashSet::new(),
+            rows: vec![0; shape.size()],
+            cols: vec![0; shape.size()],
+            blocks: vec![0; shape.size()],
+            candidates: Candidates::new(shape, &[]),
+            generated: None
         }
     }
 
-    fn convert_to_index(row: usize, col: usize) -> Result<SudokoIndex, String> {
-        // Validate that row is betwen 1 and 9
-        let (row_block, internal_row) = match row {
-            1..=3 => (1, row),
-            4..=6 => (2, row-3),
-            7..=9 => (3, row-6),
-            _ => return Err("Row must be from 1 to 9".to_owned())
-        };
-
-        // Validate that col is betwen 1 and 9
-        let (col_block, internal_col) = match col {
-            1..=3 => (1, col),
-            4..=6 => (2, col-3),
-            7..=9 => (3, col-6),
-            _ => return Err("Column must be from 1 to 9".to_owned())
-        };
+    pub fn shape(&self) -> Shape {
+        self.shape
+    }
 
-        let index_block = (row_block - 1) * 3 + (col_block - 1);
+    // Index of the cell, rows and columns are numbered from 1
+    fn convert_to_index(&self, row: usize, col: usize) -> Result<usize, SudokoError> {
+        let size = self.shape.size();
 
-        Ok(SudokoIndex {
-            row, col, row_block, col_block,
-            internal_row, internal_col, index_block
-        })
+        match (row, col) {
+            (1.., 1..) if row <= size && col <= size => Ok((row - 1) * size + (col - 1)),
+            _ => Err(SudokoError::OutOfRange { row, col })
+        }
     }
 
-    pub fn get_value(&self, row: usize, col: usize) -> Result<&SudokoValue, String> {
+    pub fn get_value(&self, row: usize, col: usize) -> Result<&SudokoValue, SudokoError> {
 
-        let index = Sudoko::convert_to_index(row, col)?;
+        let cell = self.convert_to_index(row, col)?;
 
-        Ok(self.data[index.index_block].get_value(index.internal_row, index.internal_col).unwrap())
+        Ok(&self.data[cell])
     }
 
-    pub fn set_value(&mut self, row: usize, col: usize, value: SudokoValue) -> Result<(), String> {
+    pub fn set_value(&mut self, row: usize, col: usize, value: SudokoValue) -> Result<(), SudokoError> {
         
         // Check if the value is locked
         if self.lock.contains(&(row, col)) {
-            return Err("Value is locked!".to_string())
+            return Err(SudokoError::Locked { row, col })
         }
         
-        // Validate that row is betwen 1 and 9
-        let index = Sudoko::convert_to_index(row, col)?;
-
-        self.data[index.index_block].set_value(index.internal_row, index.internal_col, value)?;
-
-        Ok(())
-    }
-
-    pub fn select_value(&mut self, row: usize, col: usize) -> Result<(), String> {
-        let index = Sudoko::convert_to_index(row, col)?;
-
-        // Unselect the old value
-        if let Some(select_index) = self.selected {
+        let cell = self.convert_to_index(row, col)?;
 
-            if index == select_index {
-                return Ok(())
+        // Only the digits of this board can be placed
+        if let Some(digit) = value.to_value() {
+            if !(1..=self.shape.size()).contains(&digit) {
+                return Err(SudokoError::InvalidDigit(digit))
             }
+        }
 
-            let selected_value = self.data[select_index.index_block].get_value(select_index.internal_row, select_index.internal_col)?;
-
-            let new_value = match selected_value {
-                SudokoValue::One(_) => SudokoValue::One(false),
-                SudokoValue::Two(_) => SudokoValue::Two(false),
-                SudokoValue::Three(_) => SudokoValue::Three(false),
-                SudokoValue::Four(_) => SudokoValue::Four(false),
-                SudokoValue::Five(_) => SudokoValue::Five(false),
-                SudokoValue::Six(_) => SudokoValue::Six(false),
-                SudokoValue::Seven(_) => SudokoValue::Seven(false),
-                SudokoValue::Eight(_) => SudokoValue::Eight(false),
-                SudokoValue::Nine(_) => SudokoValue::Nine(false),
-                SudokoValue::Empty(_) => SudokoValue::Empty(false),
-            };
-
-            self.data[select_index.index_block].set_value(select_index.internal_row, select_index.internal_col, new_value)?;
-        }
-
-        let current_value = self.data[index.index_block].get_value(index.internal_row, index.internal_col)?;
-
-        let new_value = match current_value {
-            SudokoValue::One(_) => SudokoValue::One(true),
-            SudokoValue::Two(_) => SudokoValue::Two(true),
-            SudokoValue::Three(_) => SudokoValue::Three(true),
-            SudokoValue::Four(_) => SudokoValue::Four(true),
-            SudokoValue::Five(_) => SudokoValue::Five(true),
-            SudokoValue::Six(_) => SudokoValue::Six(true),
-            SudokoValue::Seven(_) => SudokoValue::Seven(true),
-            SudokoValue::Eight(_) => SudokoValue::Eight(true),
-            SudokoValue::Nine(_) => SudokoValue::Nine(true),
-            SudokoValue::Empty(_) => SudokoValue::Empty(true),
-        };
+        let previous = self.data[cell].to_value().unwrap_or(0);
 
-        self.data[index.index_block].set_value(index.internal_row, index.internal_col, new_value)?;
+        self.data[cell] = value;
+        self.update_used(cell);
 
-        // Set the new value as the selected
-        self.selected = Some(index);
+        // Filling an empty cell keeps the eliminations found so far, any
+        // other change can bring candidates back so they are rebuilt
+        match value.to_value() {
+            Some(digit) if previous == 0 => self.candidates.place(cell, digit),
+            Some(digit) if previous == digit => (),
+            None if previous == 0 => (),
+            _ => self.candidates = Candidates::new(self.shape, &self.to_grid())
+        }
 
         Ok(())
     }
 
-    pub fn unselect_value(&mut self) -> Result<(), String> {
-        if let Some(select_index) = self.selected {
-            let selected_value = self.data[select_index.index_block].get_value(select_index.internal_row, select_index.internal_col)?;
-
-            let new_value = match selected_value {
-                SudokoValue::One(_) => SudokoValue::One(false),
-                SudokoValue::Two(_) => SudokoValue::Two(false),
-                SudokoValue::Three(_) => SudokoValue::Three(false),
-                SudokoValue::Four(_) => SudokoValue::Four(false),
-                SudokoValue::Five(_) => SudokoValue::Five(false),
-                SudokoValue::Six(_) => SudokoValue::Six(false),
-                SudokoValue::Seven(_) => SudokoValue::Seven(false),
-                SudokoValue::Eight(_) => SudokoValue::Eight(false),
-                SudokoValue::Nine(_) => SudokoValue::Nine(false),
-                SudokoValue::Empty(_) => SudokoValue::Empty(false)
-            };
+    // Recompute the used digits of the houses through a cell. A digit can be
+    // in a house more than once while the grid breaks the rules, so clearing
+    // a single bit on removal is not enough.
+    fn update_used(&mut self, cell: usize) {
+        let shape = self.shape;
+        let (row, col, block) = (shape.row_of(cell), shape.col_of(cell), shape.box_of(cell));
 
-            self.data[select_index.index_block].set_value(select_index.internal_row, select_index.internal_col, new_value)?;
-        }
+        self.rows[row] = self.used(House::Row(row));
+        self.cols[col] = self.used(House::Col(col));
+        self.blocks[block] = self.used(House::Block(block));
+    }
 
-        self.selected = None;
+    fn used(&self, house: House) -> u32 {
+        house.cells_in(self.shape)
+            .into_iter()
+            .filter_map(|cell| self.data[cell].to_value())
+            .fold(0, |mask, digit| mask | 1 << digit)
+    }
 
-        Ok(())
+    pub fn candidates(&self) -> &Candidates {
+        &self.candidates
     }
 
-    pub fn validate(&self) -> Result<(), String> {
+    pub fn validate(&self) -> Result<(), SudokoError> {
 
         // Check of all of the blocks
-        for index_block in 0..9 {
-            self.data[index_block].validate().map_err(|e| {
-                match index_block {
-                    0 => format!("{e} Found at top-left block!"),
-                    1 => format!("{e} Found at top-center block!"),
-                    2 => format!("{e} Found at top-right block!"),
-                    3 => format!("{e} Found at middel-left block!"),
-                    4 => format!("{e} Found at middel-center block!"),
-                    5 => format!("{e} Found at middel-right block!"),
-                    6 => format!("{e} Found at bottom-left block!"),
-                    7 => format!("{e} Found at bottom-center block!"),
-                    8 => format!("{e} Found at bottom-right block!"),
-                    _ => e
-                }
-                
-            })?
+        for block in 1..=self.shape.size() {
+            self.validate_block(block)?
         }
 
         // Check each of the rows
-        for row in 1..=9 {
+        for row in 1..=self.shape.size() {
             self.validate_row(row)?
         }
 
         // Check each of the columns
-        for col in 1..=9 {
+        for col in 1..=self.shape.size() {
             self.validate_col(col)?
         }
 
         Ok(())
     }
 
-    pub fn validate_row(&self, row: usize) -> Result<(), String> {
-        let mut value_set: HashSet<usize> = HashSet::new();
+    // Blocks are numbered from 1 at the top-left to the bottom-right
+    pub fn validate_block(&self, block: usize) -> Result<(), SudokoError> {
+        match block {
+            1.. if block <= self.shape.size() => self.validate_house(House::Block(block - 1)),
+            _ => Err(SudokoError::BlockOutOfRange(block))
+        }
+    }
 
-        for col in 1..=9 {
-            let index = Sudoko::convert_to_index(row, col)?;
+    pub fn validate_row(&self, row: usize) -> Result<(), SudokoError> {
+        self.convert_to_index(row, 1)?;
+        self.validate_house(House::Row(row - 1))
+    }
 
-            match self.data[index.index_block]
-                    .get_value(index.internal_row, index.internal_col)?
-                    .to_value() {
-                None => (),
-                Some(value) => {
-                    if value_set.contains(&value) {
-                        return Err(format!("Value '{value}' is already present in row '{row}' at column '{col}'"))
-                    } else {
-                        value_set.insert(value);
-                    }
-                }
-            }
+    pub fn validate_col(&self, col: usize) -> Result<(), SudokoError> {
+        self.convert_to_index(1, col)?;
+        self.validate_house(House::Col(col - 1))
+    }
+
+    fn validate_house(&self, house: House) -> Result<(), SudokoError> {
+        match self.house_conflicts(house).first() {
+            None => Ok(()),
+            Some(conflict) => Err(SudokoError::Conflict(*conflict))
         }
+    }
 
-        Ok(())
+    // Every pair of cells holding the same value in a row, column or block,
+    // blocks first like `validate`
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let mut conflicts = Vec::new();
+
+        for house in House::all_in(self.shape) {
+            conflicts.extend(self.house_conflicts(house));
+        }
 
+        conflicts
     }
 
-    pub fn validate_col(&self, col: usize) -> Result<(), String> {
-        let mut value_set: HashSet<usize> = HashSet::new();
+    fn house_conflicts(&self, house: House) -> Vec<Conflict> {
+        let mut conflicts = Vec::new();
+        // Cells seen so far for every value
+        let mut seen: HashMap<usize, Vec<Cursor>> = HashMap::new();
 
-        for row in 1..=9 {
-            let index = Sudoko::convert_to_index(row, col)?;
+        for cell in house.cells_in(self.shape) {
+            let cursor = self.shape.cursor_of(cell);
 
-            match self.data[index.index_block]
-                    .get_value(index.internal_row, index.internal_col)?
-                    .to_value() {
-                None => (),
-                Some(value) => {
-                    if value_set.contains(&value) {
-                        return Err(format!("Value '{value}' is already present in colum '{col}' at row '{row}'"))
-                    } else {
-                        value_set.insert(value);
-                    }
+            if let Some(value) = self.data[cell].to_value() {
+                let cells = seen.entry(value).or_default();
+
+                for first in cells.iter() {
+                    conflicts.push(Conflict { value, house, cells: [*first, cursor] });
                 }
+                cells.push(cursor);
             }
         }
 
-        Ok(())
+        conflicts
     }
 
-    pub fn solve(&mut self) -> Result<(), String> {
-        
-        self.solve_step()?;
+    // The statistics stay empty on the boards the backtracker is not used for
+    pub fn solve(&mut self) -> Result<SolveStats, SudokoError> {
+        let mut solver = solver::default_for(self.shape);
+        self.solve_with(solver.as_mut())?;
 
-        Ok(())
+        Ok(solver.stats().unwrap_or_default())
     }
 
-    pub fn solve_step(&mut self) -> Result<Cursor, String> {
-
-        let mut blocks_values: HashMap<usize, Vec<(SudokoIndex, Vec<SudokoValue>)>> = HashMap::new();
-
-        // init hashmap
-        for i in 0..9 {
-            blocks_values.insert(i, Vec::new());
-        }
-
-        for row in 1..=9 {
-            for col in 1..=9 {
-                match self.get_value(row, col)? {
-                    SudokoValue::Empty(_) => {
-                        let possible_values = self.find_possible_values(row, col)?;
-                        if let Some(mut values) = possible_values {
-                            if values.len() == 1 {
-                                if let Some(value) = values.pop() {
-                                    match self.set_value(row, col, value) {
-                                        Err(_) => (),
-                                        Ok(_) => return Ok(Cursor {row, col})
-                                    }
-                                }
-                            } else {
-                                let index = Sudoko::convert_to_index(row, col)?;
-                                if let Some(block) = blocks_values.get_mut(&index.index_block) {
-                                    block.push((index, values))
-                                }
-                            }
-                        }
-                    },
-                    _ => continue
-                }
-                
-            }
-        }
+    pub fn solve_with(&mut self, solver: &mut dyn Solver) -> Result<(), SudokoError> {
 
-        // Check each block individually, and find a unique value
-        for i in 0..9 {
-            let mut by_value: HashMap<&SudokoValue, Vec<&SudokoIndex>> = HashMap::new();
-            if let Some(block) = blocks_values.get(&i) {
-                for (index, group) in block {
-                    for value in group {
-                        if !by_value.contains_key(&value) {
-                            by_value.insert(value, Vec::new());
-                        }
+        self.validate()?;
 
-                        if let Some(add_value) = by_value.get_mut(&value) {
-                            add_value.push(index);
-                        }
-                    }
-                }
+        let grid = self.to_grid();
+        let solution = match solver.solve(self.shape, &grid) {
+            Some(solution) => solution,
+            // The search engines only fail without a solution, a solver that
+            // gives up early is told apart by the exact cover search
+            None => match DlxSolver.count_solutions(self.shape, &grid, 1) {
+                0 => return Err(SudokoError::NoSolution),
+                _ => return Err(SudokoError::Unsolved { solver: solver.name() })
             }
+        };
 
-            for (value, indexes) in by_value {
-                if indexes.len() == 1 {
+        // Fill in the empty cells, locked cells are never touched
+        for row in 1..=self.shape.size() {
+            for col in 1..=self.shape.size() {
+                if self.is_locked(row, col) {
+                    continue;
+                }
 
-                    if let Some(index) = indexes.get(0) {
-                        match self.set_value(index.row, index.col, *value) {
-                            Err(_) => (),
-                            Ok(_) => return Ok(Cursor {row: index.row, col: index.col})
-                        }
-                    }
-                    
+                if let SudokoValue::Empty = self.get_value(row, col)? {
+                    self.set_value(row, col, SudokoValue::new(Some(solution[self.convert_to_index(row, col)?]))?)?;
                 }
             }
+        }
+
+        Ok(())
+    }
+
+    // Counting with the exact cover search, the backtracker is too slow on
+    // sparse grids of the larger boards
+    pub fn count_solutions(&self, limit: usize) -> Result<usize, SudokoError> {
+        self.count_solutions_with(&mut DlxSolver, limit)
+    }
+
+    pub fn count_solutions_with(&self, solver: &mut dyn Solver, limit: usize) -> Result<usize, SudokoError> {
 
+        // A grid breaking the rules can never be completed
+        if self.validate().is_err() {
+            return Ok(0)
         }
 
-        Ok(Cursor::new())
+        Ok(solver.count_solutions(self.shape, &self.to_grid(), limit))
     }
 
-    pub fn find_possible_values(&self, row: usize, col: usize) -> Result<Option<Vec<SudokoValue>>, String> {
+    pub fn has_unique_solution(&self) -> Result<bool, SudokoError> {
+        Ok(self.count_solutions(2)? == 1)
+    }
 
-        let index = Sudoko::convert_to_index(row, col)?;
+    pub fn generate() -> Result<Sudoko, SudokoError> {
+        Sudoko::generate_with(GeneratorOptions::default())
+    }
 
-        let mut possible_values_set = SudokoValue::full_hashset()?;
+    pub fn generate_from_seed(seed: u64) -> Result<Sudoko, SudokoError> {
+        Sudoko::generate_with(GeneratorOptions { seed: Some(seed), ..GeneratorOptions::default() })
+    }
 
-        // Check values in rows
-        for row_i in 1..=9 {
-            if row_i == row { continue; }
-            let temp_index = Sudoko::convert_to_index(row_i, col)?;
-            let value = self.data[temp_index.index_block].get_value(temp_index.internal_row, temp_index.internal_col)?;
-            if possible_values_set.contains(&value) {
-                possible_values_set.remove(&value);
-            }
+    pub fn generate_with_difficulty(difficulty: Difficulty) -> Result<Sudoko, SudokoError> {
+        Sudoko::generate_with(GeneratorOptions { difficulty: Some(difficulty), ..GeneratorOptions::default() })
+    }
+
+    // When a difficulty is requested the following seeds are tried until a
+    // puzzle of that difficulty is found, the seed of that puzzle is kept
+    pub fn generate_with(options: GeneratorOptions) -> Result<Sudoko, SudokoError> {
+
+        if options.difficulty.is_some() && !options.shape.is_classic() {
+            return Err(SudokoError::UnsupportedShape(options.shape))
         }
 
-        // Check values in cols
-        for col_i in 1..=9 {
-            if col_i == col { continue; }
-            let temp_index = Sudoko::convert_to_index(row, col_i)?;
-            let value = self.data[temp_index.index_block].get_value(temp_index.internal_row, temp_index.internal_col)?;
-            if possible_values_set.contains(&value) {
-                possible_values_set.remove(&value);
-            }
+        if options.minimal && options.shape.size() > generator::MAX_MINIMAL_SIZE {
+            return Err(SudokoError::MinimalUnsupported(options.shape))
         }
 
-        // Remove values already in same block
-        for value in self.data[index.index_block].get_values() {
-            if possible_values_set.contains(&value) {
-                possible_values_set.remove(&value);
+        let first_seed = options.seed.unwrap_or_else(rand::random);
+
+        for attempt in 0..MAX_GENERATE_ATTEMPTS {
+            let seed = first_seed.wrapping_add(attempt);
+            let mut generator = Generator::from_seed(seed).with_shape(options.shape);
+
+            let mut grid = generator.puzzle(options.symmetry);
+            if options.minimal {
+                grid = generator.minimize(grid);
             }
-        }
 
-        let mut possible_values: Vec<SudokoValue> = Vec::new();
+            if let Some(difficulty) = options.difficulty {
+                if rating::rate_grid(Sudoko::classic(options.shape, &grid)?)?.difficulty != difficulty {
+                    continue;
+                }
+            }
 
-        for value in possible_values_set.into_iter().sorted() {
-            possible_values.push(value);
-        }
+            let mut sudoko = Sudoko::from_grid(options.shape, &grid)?;
 
-        if possible_values.len() == 0 {
-            // No value
-            return Ok(None)
+            // The remaining clues are the givens of the puzzle
+            sudoko.lock()?;
+            sudoko.generated = Some(GeneratorOptions { seed: Some(seed), ..options });
+
+            return Ok(sudoko)
         }
 
-        Ok(Some(possible_values))
+        Err(SudokoError::GenerationFailed { difficulty: options.difficulty, attempts: MAX_GENERATE_ATTEMPTS })
     }
 
-    pub fn lock(&mut self) -> Result<(), String> {
+    // Number of givens, the locked cells of the puzzle
+    pub fn clues(&self) -> usize {
+        self.lock.len()
+    }
 
-        for row in 1..=9 {
-            for col in 1..=9 {
-                let index = Sudoko::convert_to_index(row, col)?;
+    // No single clue can be removed without losing the unique solution
+    pub fn is_minimal(&self) -> Result<bool, SudokoError> {
 
-                let value = self.data[index.index_block].get_value(index.internal_row, index.internal_col)?;
+        if self.shape.size() > generator::MAX_MINIMAL_SIZE {
+            return Err(SudokoError::MinimalUnsupported(self.shape))
+        }
 
-                match value {
-                    SudokoValue::Empty(_) => (),
-                    _ => {
-                        // Add value into lock
-                        self.lock.insert((row, col));
-                    }
-                }
+        if !self.has_unique_solution()? {
+            return Ok(false)
+        }
 
+        let mut grid = self.to_grid();
+
+        for index in 0..grid.len() {
+            let value = grid[index];
+            if value == 0 {
+                continue;
+            }
+
+            grid[index] = 0;
+            if Dlx::new(self.shape, &grid).count_solutions(2) == 1 {
+                return Ok(false)
             }
+            grid[index] = value;
         }
 
-        Ok(())
+        Ok(true)
     }
 
-    pub fn is_locked(&self, row: usize, col: usize) -> bool {
-        self.lock.contains(&(row, col))
+    // Options the puzzle was generated with, None if it was entered by hand
+    pub fn generator_options(&self) -> Option<&GeneratorOptions> {
+        self.generated.as_ref()
     }
 
-    pub fn unlock(&mut self) {
-        self.lock.clear()
+    pub fn seed(&self) -> Option<u64> {
+        self.generated.and_then(|options| options.seed)
     }
 
-}
+    fn from_grid(shape: Shape, grid: &[usize]) -> Result<Sudoko, SudokoError> {
+        let mut sudoko = Sudoko::with_shape(shape);
 
-impl Display for Sudoko {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-
-        for row in 1..=9 {
-            for row_height in 1..=2 {
-                match row_height {
-                    1 => match row {
-                        1 => writeln!(f, "┏━━━┯━━━┯━━━┳━━━┯━━━┯━━━┳━━━┯━━━┯━━━┓")?,
-                        2 => writeln!(f, "┠───┼───┼───╂───┼───┼───╂───┼───┼───┨")?,
-                        3 => writeln!(f, "┠───┼───┼───╂───┼───┼───╂───┼───┼───┨")?,
-                        4 => writeln!(f, "┣━━━┿━━━┿━━━╋━━━┿━━━┿━━━╋━━━┿━━━┿━━━┫")?,
-                        5 => writeln!(f, "┠───┼───┼───╂───┼───┼───╂───┼───┼───┨")?,
-                        6 => writeln!(f, "┠───┼───┼───╂───┼───┼───╂───┼───┼───┨")?,
-                        7 => writeln!(f, "┣━━━┿━━━┿━━━╋━━━┿━━━┿━━━╋━━━┿━━━┿━━━┫")?,
-                        8 => writeln!(f, "┠───┼───┼───╂───┼───┼───╂───┼───┼───┨")?,
-                        9 => writeln!(f, "┠───┼───┼───╂───┼───┼───╂───┼───┼───┨")?,
-                        _ => writeln!(f, "")?
-                    }
-                    2 => {
-                        for col in 1..=9 {
-                            match col {
-                                1 => {
-                                    match self.is_locked(row, col) {
-                                        false => write!(f, "┃ {} ", self.get_value(row, col).unwrap())?,
-                                        true => write!(f, "┃ {} ", self.get_value(row, col).unwrap()
-                                            .to_string().as_str().underline())?
-                                    }
-                                },
-                                2 => {
-                                    match self.is_locked(row, col) {
-                                        false => write!(f, "│ {} ", self.get_value(row, col).unwrap())?,
-                                        true => write!(f, "│ {} ", self.get_value(row, col).unwrap()
-                                            .to_string().as_str().underline())?
-                                    }
-                                },
-                                3 => {
-                                    match self.is_locked(row, col) {
-                                        false => write!(f, "│ {} ", self.get_value(row, col).unwrap())?,
-                                        true => write!(f, "│ {} ", self.get_value(row, col).unwrap()
-                                            .to_string().as_str().underline())?
-                                    }
-                                },
-                                4 => {
-                                    match self.is_locked(row, col) {
-                                        false => write!(f, "┃ {} ", self.get_value(row, col).unwrap())?,
-                                        true => write!(f, "┃ {} ", self.get_value(row, col).unwrap()
-                                            .to_string().as_str().underline())?
-                                    }
-                                },
-                                5 => {
-                                    match self.is_locked(row, col) {
-                                        false => write!(f, "│ {} ", self.get_value(row, col).unwrap())?,
-                                        true => write!(f, "│ {} ", self.get_value(row, col).unwrap()
-                                            .to_string().as_str().underline())?
-                                    }
-                                },
-                                6 => {
-                                    match self.is_locked(row, col) {
-                                        false => write!(f, "│ {} ", self.get_value(row, col).unwrap())?,
-                                        true => write!(f, "│ {} ", self.get_value(row, col).unwrap()
-                                            .to_string().as_str().underline())?
-                                    }
-                                },
-                                7 => {
-                                    match self.is_locked(row, col) {
-                                        false => write!(f, "┃ {} ", self.get_value(row, col).unwrap())?,
-                                        true => write!(f, "┃ {} ", self.get_value(row, col).unwrap()
-                                            .to_string().as_str().underline())?
-                                    }
-                                },
-                                8 => {
-                                    match self.is_locked(row, col) {
-                                        false => write!(f, "│ {} ", self.get_value(row, col).unwrap())?,
-                                        true => write!(f, "│ {} ", self.get_value(row, col).unwrap()
-                                            .to_string().as_str().underline())?
-                                    }
-                                },
-                                9 => {
-                                    match self.is_locked(row, col) {
-                                        false => write!(f, "│ {} ", self.get_value(row, col).unwrap())?,
-                                        true => write!(f, "│ {} ", self.get_value(row, col).unwrap()
-                                            .to_string().as_str().underline())?
-                                    }
-                                },
-                                _ => writeln!(f, "")?
-                            }
-                        }
-                        writeln!(f, "┃")?;
-                    },
-                    _ => ()
-                }
+        for row in 1..=shape.size() {
+            for col in 1..=shape.size() {
+                sudoko.set_value(row, col, SudokoValue::new(Some(grid[(row - 1) * shape.size() + (col - 1)]))?)?;
             }
         }
 
-        write!(f, "┗━━━┷━━━┷━━━┻━━━┷━━━┷━━━┻━━━┷━━━┷━━━┛")
+        Ok(sudoko)
+    }
 
+    // Flat row-major copy of the grid with 0 for empty cells
+    pub(crate) fn to_grid(&self) -> Vec<usize> {
+        self.data.iter().map(|value| value.to_value().unwrap_or(0)).collect()
     }
-}
 
-#[allow(dead_code)]
-#[derive(Debug, Clone, Copy)]
-pub struct SudokoBlock {
-    data: [SudokoValue; 9]
-}
+    // The grid as the techniques take it, only on the classic board
+    pub(crate) fn classic_grid(&self) -> Result<[usize; 81], SudokoError> {
+        Sudoko::classic(self.shape, &self.to_grid())
+    }
 
-impl SudokoBlock {
-    fn new() -> SudokoBlock {
+    fn classic(shape: Shape, grid: &[usize]) -> Result<[usize; 81], SudokoError> {
+        match shape.is_classic() {
+            false => Err(SudokoError::UnsupportedShape(shape)),
+            true => grid.try_into().map_err(|_| SudokoError::UnsupportedShape(shape))
+        }
+    }
 
-        SudokoBlock {
-            data: [SudokoValue::new(None).unwrap(); 9]
+    // Find and apply the simplest deduction, either placing a value or
+    // removing candidates from the candidate grid
+    pub fn solve_step(&mut self) -> Result<StepOutcome, SudokoError> {
+        self.solve_step_with(&mut TechniqueSolver)
+    }
+
+    // Apply the next step of any solver, search engines fill in one value
+    // of their solution
+    pub fn solve_step_with(&mut self, solver: &mut dyn Solver) -> Result<StepOutcome, SudokoError> {
+
+        self.validate()?;
+
+        let deduction = match self.next_deduction(solver)? {
+            None => return Ok(self.no_deduction()),
+            Some(deduction) => deduction
+        };
+
+        match deduction.placement {
+            None => self.candidates.apply(&deduction),
+            Some((cell, value)) => {
+                let cursor = self.shape.cursor_of(cell);
+                self.set_value(cursor.row, cursor.col, SudokoValue::new(Some(value))?)?
+            }
         }
 
+        Ok(StepOutcome::Deduced(Hint::new(&deduction, self.shape)))
     }
 
-    pub fn convert_to_index(&self, row: usize, col: usize) -> Result<SudokoIndex, String> {
-        match row {
-            1..=3 => (),
-            _ => return Err("Row must be from 1 to 3".to_owned())
+    // The hint `solve_step` would apply, without changing the grid
+    pub fn next_hint(&self) -> Result<StepOutcome, SudokoError> {
+        match self.next_deduction(&mut TechniqueSolver)? {
+            None => Ok(self.no_deduction()),
+            Some(deduction) => Ok(StepOutcome::Deduced(Hint::new(&deduction, self.shape)))
         }
+    }
 
-        // Validate that row is betwen 1 and 3
-        match col {
-            1..=3 => (),
-            _ => return Err("Colum must be from 1 to 3".to_owned())
+    fn no_deduction(&self) -> StepOutcome {
+        match self.candidates.is_solved() {
+            true => StepOutcome::Solved,
+            false => StepOutcome::Stuck
         }
+    }
 
-        let index_block = (row - 1) * 3 + (col - 1);
+    fn next_deduction(&self, solver: &mut dyn Solver) -> Result<Option<Deduction>, SudokoError> {
 
-        Ok(SudokoIndex {
-            row, col, 
-            row_block: row, col_block: col,
-            internal_row: row, internal_col: col,
-            index_block
-        })
+        // Uniqueness techniques can only be used on a puzzle with one solution
+        let mut candidates = self.candidates.clone();
+        candidates.set_unique_solution(self.has_unique_solution()?);
+
+        Ok(solver.step(&candidates))
     }
 
-    pub fn get_value(&self, row: usize, col: usize) -> Result<&SudokoValue, String> {
-        let index = self.convert_to_index(row, col)?;
+    // Run the techniques from the current position until the puzzle is
+    // solved or no technique applies, without changing the puzzle
+    pub fn solve_path(&self) -> Result<SolvePath, SudokoError> {
 
-        Ok(&self.data[index.index_block])
-    }
+        self.validate()?;
 
-    pub fn get_values(&self) -> Vec<SudokoValue> {
-        
-        let mut values: Vec<SudokoValue> = Vec::new();
+        let mut candidates = self.candidates.clone();
+        candidates.set_unique_solution(self.has_unique_solution()?);
 
-        for value in self.data {
-            match value {
-                SudokoValue::One(_) => values.push(SudokoValue::One(false)),
-                SudokoValue::Two(_) => values.push(SudokoValue::Two(false)),
-                SudokoValue::Three(_) => values.push(SudokoValue::Three(false)),
-                SudokoValue::Four(_) => values.push(SudokoValue::Four(false)),
-                SudokoValue::Five(_) => values.push(SudokoValue::Five(false)),
-                SudokoValue::Six(_) => values.push(SudokoValue::Six(false)),
-                SudokoValue::Seven(_) => values.push(SudokoValue::Seven(false)),
-                SudokoValue::Eight(_) => values.push(SudokoValue::Eight(false)),
-                SudokoValue::Nine(_) => values.push(SudokoValue::Nine(false)),
-                SudokoValue::Empty(_) => (),
+        let mut steps = Vec::new();
+        while !candidates.is_solved() {
+            match candidates.find_deduction() {
+                None => break,
+                Some(deduction) => {
+                    steps.push(Hint::new(&deduction, self.shape));
+                    candidates.apply(&deduction);
+                }
             }
         }
 
-        values
+        Ok(SolvePath { steps, solved: candidates.is_solved() })
+    }
+
+    // Candidates left in the candidate grid, including eliminations
+    pub fn get_candidates(&self, row: usize, col: usize) -> Result<Option<Vec<SudokoValue>>, SudokoError> {
+
+        let cell = self.convert_to_index(row, col)?;
+
+        let mask = self.candidates.mask(cell);
+        if mask == 0 {
+            return Ok(None)
+        }
+
+        let mut values: Vec<SudokoValue> = Vec::new();
+        for value in (1..=self.shape.size()).filter(|value| mask & (1 << value) != 0) {
+            values.push(SudokoValue::new(Some(value))?);
+        }
 
+        Ok(Some(values))
     }
 
-    pub fn set_value(&mut self, row: usize, col: usize, value: SudokoValue) -> Result<(), String> {
-        let index = self.convert_to_index(row, col)?;
+    // Digits not used yet in the row, column and block of the cell
+    pub fn find_possible_values(&self, row: usize, col: usize) -> Result<Option<Vec<SudokoValue>>, SudokoError> {
 
-        self.data[index.index_block] = value;
+        let cell = self.convert_to_index(row, col)?;
 
-        Ok(())
+        let used = self.rows[row - 1] | self.cols[col - 1] | self.blocks[self.shape.box_of(cell)];
+        let possible = !used & self.shape.full_mask();
+
+        if possible == 0 {
+            // No value
+            return Ok(None)
+        }
+
+        let mut possible_values: Vec<SudokoValue> = Vec::new();
+        for value in 1..=self.shape.size() {
+            if possible & (1 << value) != 0 {
+                possible_values.push(SudokoValue::new(Some(value))?);
+            }
+        }
+
+        Ok(Some(possible_values))
     }
-    
-    pub fn validate(&self) -> Result<(), String> {
-        let mut value_set: HashSet<usize> = HashSet::new();
-
-        for value in self.data {
-            match value.to_value() {
-                None => (),
-                Some(value) => {
-                    if value_set.contains(&value) {
-                        return Err(format!("Value {value} is already present!"))
-                    } else {
-                        value_set.insert(value);
+
+    pub fn lock(&mut self) -> Result<(), SudokoError> {
+
+        for row in 1..=self.shape.size() {
+            for col in 1..=self.shape.size() {
+                let value = self.get_value(row, col)?;
+
+                match value {
+                    SudokoValue::Empty => (),
+                    _ => {
+                        // Add value into lock
+                        self.lock.insert((row, col));
                     }
                 }
+
             }
         }
 
         Ok(())
     }
 
+    pub fn is_locked(&self, row: usize, col: usize) -> bool {
+        self.lock.contains(&(row, col))
+    }
+
+    pub fn unlock(&mut self) {
+        self.lock.clear()
+    }
+
+}
+
+impl Display for Sudoko {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", SudokoView::new(self, None))
+    }
+}
+
+// The one-line format, every cell in row order with 0 or . for an empty
+// cell and whitespace ignored. The values are locked as the givens. The
+// number of cells decides the board, 81 of them make the classic one.
+impl FromStr for Sudoko {
+    type Err = SudokoError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let symbols: Vec<char> = text.chars().filter(|character| !character.is_whitespace()).collect();
+
+        let shape = Shape::all()
+            .into_iter()
+            .find(|shape| shape.cells() == symbols.len())
+            .ok_or(SudokoError::InvalidLength(symbols.len()))?;
+
+        let mut grid = Vec::new();
+        for symbol in symbols {
+            grid.push(match symbol {
+                '0' | '.' => 0,
+                _ => shape::value_of(symbol).ok_or(SudokoError::InvalidSymbol(symbol))?
+            });
+        }
+
+        let mut sudoko = Sudoko::from_grid(shape, &grid)?;
+        sudoko.lock()?;
+
+        Ok(sudoko)
+    }
 }
 
-#[allow(dead_code)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum SudokoValue {
-    One(bool), Two(bool), Three(bool),
-    Four(bool), Five(bool), Six(bool),
-    Seven(bool), Eight(bool), Nine(bool),
-    Empty(bool)
+    // 1 up to the size of the board
+    Digit(usize),
+    Empty
 }
 
+// Colours of the digits 1 to 9, larger digits start over
+const COLORS: [Color; 9] = [
+    Color::BrightBlue, Color::BrightCyan, Color::BrightGreen,
+    Color::BrightMagenta, Color::BrightMagenta, Color::BrightRed,
+    Color::BrightYellow, Color::BrightBlue, Color::BrightRed
+];
+
 impl SudokoValue {
-    pub fn new(value: Option<usize>) -> Result<SudokoValue, String> {
+    pub fn new(value: Option<usize>) -> Result<SudokoValue, SudokoError> {
         match value {
-            None => Ok(SudokoValue::Empty(false)),
+            None => Ok(SudokoValue::Empty),
             Some(x) => match x {
-                0 => Ok(SudokoValue::Empty(false)),
-                1 => Ok(SudokoValue::One(false)),
-                2 => Ok(SudokoValue::Two(false)),
-                3 => Ok(SudokoValue::Three(false)),
-                4 => Ok(SudokoValue::Four(false)),
-                5 => Ok(SudokoValue::Five(false)),
-                6 => Ok(SudokoValue::Six(false)),
-                7 => Ok(SudokoValue::Seven(false)),
-                8 => Ok(SudokoValue::Eight(false)),
-                9 => Ok(SudokoValue::Nine(false)),
-                _ => Err("Value must be None or 1-9".to_owned())
+                0 => Ok(SudokoValue::Empty),
+                1..=Shape::MAX_SIZE => Ok(SudokoValue::Digit(x)),
+                _ => Err(SudokoError::InvalidDigit(x))
             }
             
         }
     }
 
-    pub fn full_hashset() -> Result<HashSet<SudokoValue>, String> {
+    // Every digit of a board of the given shape
+    pub fn full_hashset(shape: Shape) -> Result<HashSet<SudokoValue>, SudokoError> {
         let mut set: HashSet<SudokoValue> = HashSet::new();
-        for i in 1..=9 {
+        for i in 1..=shape.size() {
             set.insert(SudokoValue::new(Some(i))?);
         }
         Ok(set)
@@ -634,83 +633,133 @@ impl SudokoValue {
 
     pub fn to_value(&self) -> Option<usize> {
         match self {
-            SudokoValue::One(_) => Some(1),
-            SudokoValue::Two(_) => Some(2),
-            SudokoValue::Three(_) => Some(3),
-            SudokoValue::Four(_) => Some(4),
-            SudokoValue::Five(_) => Some(5),
-            SudokoValue::Six(_) => Some(6),
-            SudokoValue::Seven(_) => Some(7),
-            SudokoValue::Eight(_) => Some(8),
-            SudokoValue::Nine(_) => Some(9),
-            SudokoValue::Empty(_) => None
+            SudokoValue::Digit(digit) => Some(*digit),
+            SudokoValue::Empty => None
+        }
+    }
+
+    // Colour the digit is drawn in, the renderer decides the style
+    pub fn color(&self) -> Option<Color> {
+        match self {
+            SudokoValue::Digit(0) | SudokoValue::Empty => None,
+            SudokoValue::Digit(digit) => Some(COLORS[(digit - 1) % COLORS.len()])
         }
     }
 }
 
 impl Display for SudokoValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            SudokoValue::One(selected) => {
-                match selected {
-                    false => write!(f, "{}", "1".bright_blue().dimmed()),
-                    true => write!(f, "{}", "1".bright_blue().bold()),
-                }
-            },
-            SudokoValue::Two(selected) => {
-                match selected {
-                    false => write!(f, "{}", "2".bright_cyan().dimmed()),
-                    true => write!(f, "{}", "2".bright_cyan().bold())
-                }
-            },
-            SudokoValue::Three(selected) => {
-                match selected {
-                    false => write!(f, "{}", "3".bright_green().dimmed()),
-                    true => write!(f, "{}", "3".bright_green().bold())
-                }
-            },
-            SudokoValue::Four(selected) => {
-                match selected {
-                    false => write!(f, "{}", "4".bright_magenta().dimmed()),
-                    true => write!(f, "{}", "4".bright_magenta().bold())
-                }
-            },
-            SudokoValue::Five(selected) => {
-                match selected {
-                    false => write!(f, "{}", "5".bright_purple().dimmed()),
-                    true => write!(f, "{}", "5".bright_purple().bold())
-                }
-            },
-            SudokoValue::Six(selected) => {
-                match selected {
-                    false => write!(f, "{}", "6".bright_red().dimmed()),
-                    true => write!(f, "{}", "6".bright_red().bold()),
-                }
-            },
-            SudokoValue::Seven(selected) => {
-                match selected {
-                    false => write!(f, "{}", "7".bright_yellow().dimmed()),
-                    true => write!(f, "{}", "7".bright_yellow().bold())
-                }
-            },
-            SudokoValue::Eight(selected) => {
-                match selected {
-                    false => write!(f, "{}", "8".bright_blue().dimmed()),
-                    true => write!(f, "{}", "8".bright_blue().bold())
-                }
-            },
-            SudokoValue::Nine(selected) => {
-                match selected {
-                    false => write!(f, "{}", "9".bright_red().dimmed()),
-                    true => write!(f, "{}", "9".bright_red().bold())
-                }
-            },
-            SudokoValue::Empty(selected) => {
-                match selected {
-                    false => write!(f, " "),
-                    true => write!(f, "{}", " ".on_white())
-                }
-            },
+        match (self.to_value(), self.color()) {
+            (Some(value), Some(color)) => write!(f, "{}", shape::symbol(value).to_string().color(color).dimmed()),
+            _ => write!(f, " ")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(sudoko: &Sudoko) -> String {
+        sudoko.to_grid().into_iter()
+            .map(|value| match value {
+                0 => '.',
+                value => shape::symbol(value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parse_blanks_and_whitespace() {
+        let dots: Sudoko = "1.5...9...9....3826....27.5..96.....2....5......1.7...5....327...6.5...4...8.....".parse().unwrap();
+        let zeros: Sudoko = "105000900 090000382 600002705 009600000 200005000 000107000 500003270 006050004 000800000".parse().unwrap();
+
+        assert_eq!(dots.to_grid(), zeros.to_grid());
+        assert_eq!(dots.shape(), Shape::classic());
+        assert_eq!(line(&zeros), "1.5...9...9....3826....27.5..96.....2....5......1.7...5....327...6.5...4...8.....");
+    }
+
+    #[test]
+    fn parse_locks_givens() {
+        let mut sudoko: Sudoko = "4.....3...1.2...".parse().unwrap();
+
+        assert_eq!(sudoko.clues(), 4);
+        assert!(sudoko.is_locked(1, 1));
+        assert!(!sudoko.is_locked(1, 2));
+        assert_eq!(sudoko.set_value(1, 1, SudokoValue::Empty), Err(SudokoError::Locked { row: 1, col: 1 }));
+        assert!(sudoko.set_value(1, 2, SudokoValue::Digit(2)).is_ok());
+    }
+
+    #[test]
+    fn parse_other_boards() {
+        let sudoko: Sudoko = "...42..6.........32.1.....4...1...3.".parse().unwrap();
+
+        assert_eq!(sudoko.shape(), Shape::new(2, 3).unwrap());
+        assert_eq!(sudoko.get_value(1, 4).unwrap().to_value(), Some(4));
+        assert_eq!(sudoko.get_value(2, 2).unwrap().to_value(), Some(6));
+        assert_eq!(sudoko.clues(), 9);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!("1234".parse::<Sudoko>().unwrap_err(), SudokoError::InvalidLength(4));
+        assert_eq!("123x............".parse::<Sudoko>().unwrap_err(), SudokoError::InvalidSymbol('x'));
+        assert_eq!("1235............".parse::<Sudoko>().unwrap_err(), SudokoError::InvalidDigit(5));
+    }
+
+    #[test]
+    fn conflicts_in_rectangular_block() {
+        // r1c1 and r2c3 only share the 2x3 block, they would not with 3x2 blocks
+        let mut sudoko: Sudoko = "...42..6.........32.1.....4...1...3.".parse().unwrap();
+        sudoko.set_value(1, 1, SudokoValue::Digit(5)).unwrap();
+        sudoko.set_value(2, 3, SudokoValue::Digit(5)).unwrap();
+
+        assert_eq!(sudoko.conflicts(), [Conflict {
+            value: 5,
+            house: House::Block(0),
+            cells: [Cursor { row: 1, col: 1 }, Cursor { row: 2, col: 3 }]
+        }]);
+    }
+
+    #[test]
+    fn solve_finishes_on_largest_board() {
+        let mut sudoko = Sudoko::with_shape(Shape::new(5, 5).unwrap());
+        sudoko.solve().unwrap();
+
+        assert_eq!(sudoko.to_grid().iter().filter(|value| **value == 0).count(), 0);
+        assert!(sudoko.validate().is_ok());
+    }
+
+    #[test]
+    fn solve_path_rejects_conflicts() {
+        let sudoko: Sudoko = "1.5...9.5.9....3826....27.5..96.....2....5......1.7...5....327...6.5...4...8.....".parse().unwrap();
+
+        assert!(matches!(sudoko.solve_path(), Err(SudokoError::Conflict(_))));
+    }
+
+    #[test]
+    fn techniques_work_on_small_boards() {
+        let mut sudoko: Sudoko = "123434122143432.".parse().unwrap();
+
+        match sudoko.next_hint().unwrap() {
+            StepOutcome::Deduced(hint) => assert_eq!(hint.placement, Some((Cursor { row: 4, col: 4 }, 1))),
+            outcome => panic!("expected a deduction, got {outcome:?}")
         }
+
+        for line in ["4.....3...1.2...", "...42..6.........32.1.....4...1...3."] {
+            assert!(line.parse::<Sudoko>().unwrap().solve_path().unwrap().solved, "{line}");
+        }
+
+        sudoko.solve_with(&mut TechniqueSolver).unwrap();
+        assert_eq!(sudoko.get_value(4, 4).unwrap().to_value(), Some(1));
     }
-}
\ No newline at end of file
+
+    // The same seed has to give the same puzzle in every version, seeds are shared between players
+    #[test]
+    fn generate_from_seed_is_stable() {
+        assert_eq!(line(&Sudoko::generate_from_seed(1).unwrap()),
+            ".3............9..6..6....59..259...775.8.....1.9.........7..8..8...1.4.....6.4.91");
+        assert_eq!(line(&Sudoko::generate_from_seed(42).unwrap()),
+            "1.5...9...9....3826....27.5..96.....2....5......1.7...5....327...6.5...4...8.....");
+    }
+}
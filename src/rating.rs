@@ -0,0 +1,146 @@
+use std::{cmp::Ordering, collections::BTreeMap, fmt::Display};
+
+use crate::error::SudokoError;
+use crate::model::Sudoko;
+use crate::shape::Shape;
+use crate::solver::{DlxSolver, Solver};
+use crate::technique::{Candidates, Difficulty, Technique};
+
+#[derive(Debug, Clone)]
+pub struct Rating {
+    // Score of the hardest technique needed, the score of a guess when the techniques got stuck
+    pub score: f32,
+    // The band the score falls in
+    pub difficulty: Difficulty,
+    pub hardest: Option<Technique>,
+    // How often every technique was used on the way to the solution
+    pub techniques: BTreeMap<Technique, usize>,
+    pub steps: usize,
+    pub solved: bool
+}
+
+impl Rating {
+    // Number of steps that needed the hardest technique
+    pub fn hardest_count(&self) -> usize {
+        match self.hardest {
+            None => 0,
+            Some(hardest) => self.techniques.get(&hardest).copied().unwrap_or(0)
+        }
+    }
+}
+
+// Ratings are equal when they rank the same, the techniques used on the
+// way may differ
+impl PartialEq for Rating {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Rating {}
+
+// Puzzles are ordered by the hardest technique first, a puzzle needing it
+// more often is harder, and with equal counts the longer solve path is harder
+impl Ord for Rating {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score.total_cmp(&other.score)
+            .then(self.hardest_count().cmp(&other.hardest_count()))
+            .then(self.steps.cmp(&other.steps))
+    }
+}
+
+impl PartialOrd for Rating {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Rating {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.1} {}", self.score, self.difficulty)?;
+
+        match (self.solved, self.hardest) {
+            (false, _) => write!(f, " (needs guessing)"),
+            (true, None) => Ok(()),
+            (true, Some(hardest)) => write!(f, " ({hardest} x{}, {} steps)", self.hardest_count(), self.steps)
+        }
+    }
+}
+
+pub fn rate(sudoko: &Sudoko) -> Result<Rating, SudokoError> {
+    sudoko.validate()?;
+    rate_grid(sudoko.classic_grid()?)
+}
+
+// Solve with the techniques alone, always taking the simplest deduction
+// available. The hardest technique needed decides the score.
+pub fn rate_grid(grid: [usize; 81]) -> Result<Rating, SudokoError> {
+    match DlxSolver.count_solutions(Shape::classic(), &grid, 2) {
+        0 => return Err(SudokoError::NoSolution),
+        1 => (),
+        _ => return Err(SudokoError::MultipleSolutions)
+    }
+
+    let mut candidates = Candidates::new(Shape::classic(), &grid);
+    candidates.set_unique_solution(true);
+
+    let mut rating = Rating {
+        score: 0.0,
+        difficulty: Difficulty::Easy,
+        hardest: None,
+        techniques: BTreeMap::new(),
+        steps: 0,
+        solved: true
+    };
+
+    while !candidates.is_solved() {
+        match candidates.find_deduction() {
+            None => {
+                rating.score = Technique::Guess.score();
+                rating.solved = false;
+                break;
+            },
+            Some(deduction) => {
+                let technique = deduction.technique;
+
+                if rating.hardest.is_none_or(|hardest| technique.score() > hardest.score()) {
+                    rating.hardest = Some(technique);
+                    rating.score = technique.score();
+                }
+                *rating.techniques.entry(technique).or_insert(0) += 1;
+                rating.steps += 1;
+
+                candidates.apply(&deduction);
+            }
+        }
+    }
+
+    rating.difficulty = Difficulty::from_score(rating.score);
+    Ok(rating)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate_line(line: &str) -> Result<Rating, SudokoError> {
+        rate(&line.parse::<Sudoko>().unwrap())
+    }
+
+    #[test]
+    fn rates_unique_puzzle() {
+        let rating = rate_line("1.5...9...9....3826....27.5..96.....2....5......1.7...5....327...6.5...4...8.....").unwrap();
+
+        assert_eq!(rating.difficulty, Difficulty::from_score(rating.score));
+    }
+
+    #[test]
+    fn rejects_puzzles_without_single_solution() {
+        assert_eq!(rate_line("12345678.........9...............................................................").unwrap_err(),
+            SudokoError::NoSolution);
+        assert_eq!(rate_line("1.....9...9....3826....27.5..96.....2....5......1.7...5....327...6.5...4...8.....").unwrap_err(),
+            SudokoError::MultipleSolutions);
+        assert!(matches!(rate_line("11...............................................................................").unwrap_err(),
+            SudokoError::Conflict(_)));
+    }
+}
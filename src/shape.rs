@@ -0,0 +1,165 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+use crate::cursor::Cursor;
+use crate::error::SudokoError;
+
+//  Layout of a board made of boxes of `box_rows` by `box_cols` cells. A board
+//  has as many rows, columns, boxes and digits as a box has cells, so a 2x3
+//  box gives a 6x6 board with the digits 1 to 6.
+//
+//  Cells are numbered from 0 in row-major order, candidates and used digits
+//  are bitmasks where bit 1 to `size` is set when the digit is present.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct Shape {
+    box_rows: usize,
+    box_cols: usize
+}
+
+impl Default for Shape {
+    fn default() -> Self {
+        Shape::classic()
+    }
+}
+
+impl Shape {
+    // Digits are drawn as 1 to 9 followed by the letters A to P
+    pub const MAX_SIZE: usize = 25;
+
+    pub fn new(box_rows: usize, box_cols: usize) -> Result<Shape, SudokoError> {
+        let size = box_rows * box_cols;
+
+        if !(2..=Shape::MAX_SIZE).contains(&size) {
+            return Err(SudokoError::InvalidShape { box_rows, box_cols })
+        }
+
+        Ok(Shape { box_rows, box_cols })
+    }
+
+    pub fn classic() -> Shape {
+        Shape { box_rows: 3, box_cols: 3 }
+    }
+
+    // 4x4, 6x6, 9x9, 12x12, 16x16 and 25x25
+    pub fn all() -> [Shape; 6] {
+        [
+            Shape { box_rows: 2, box_cols: 2 },
+            Shape { box_rows: 2, box_cols: 3 },
+            Shape { box_rows: 3, box_cols: 3 },
+            Shape { box_rows: 3, box_cols: 4 },
+            Shape { box_rows: 4, box_cols: 4 },
+            Shape { box_rows: 5, box_cols: 5 }
+        ]
+    }
+
+    pub fn box_rows(&self) -> usize {
+        self.box_rows
+    }
+
+    pub fn box_cols(&self) -> usize {
+        self.box_cols
+    }
+
+    // Rows, columns, boxes and digits of the board
+    pub fn size(&self) -> usize {
+        self.box_rows * self.box_cols
+    }
+
+    pub fn cells(&self) -> usize {
+        self.size() * self.size()
+    }
+
+    pub fn row_of(&self, cell: usize) -> usize {
+        cell / self.size()
+    }
+
+    pub fn col_of(&self, cell: usize) -> usize {
+        cell % self.size()
+    }
+
+    // Boxes are numbered in row-major order, there are `box_rows` boxes side by side
+    pub fn box_of(&self, cell: usize) -> usize {
+        (self.row_of(cell) / self.box_rows) * self.box_rows + self.col_of(cell) / self.box_cols
+    }
+
+    pub fn cursor_of(&self, cell: usize) -> Cursor {
+        Cursor { row: self.row_of(cell) + 1, col: self.col_of(cell) + 1 }
+    }
+
+    pub fn cell_name(&self, cell: usize) -> String {
+        format!("r{}c{}", self.row_of(cell) + 1, self.col_of(cell) + 1)
+    }
+
+    // Bit 1 to `size` set
+    pub fn full_mask(&self) -> u32 {
+        ((1 << self.size()) - 1) << 1
+    }
+
+    pub fn is_classic(&self) -> bool {
+        *self == Shape::classic()
+    }
+}
+
+impl Display for Shape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.box_rows == self.box_cols {
+            true => write!(f, "{0}x{0}", self.size()),
+            false => write!(f, "{0}x{0} ({1}x{2} boxes)", self.size(), self.box_rows, self.box_cols)
+        }
+    }
+}
+
+// Character a digit is written as, 1 to 9 and then A for 10 up to P for 25
+pub fn symbol(value: usize) -> char {
+    match value {
+        1..=9 => char::from_digit(value as u32, 10).unwrap(),
+        10..=Shape::MAX_SIZE => (b'A' + (value - 10) as u8) as char,
+        _ => '?'
+    }
+}
+
+// Reverse of `symbol`, letters are only accepted in upper case
+pub fn value_of(symbol: char) -> Option<usize> {
+    match symbol {
+        '1'..='9' => symbol.to_digit(10).map(|digit| digit as usize),
+        'A'..='P' => Some(symbol as usize - 'A' as usize + 10),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The box of every cell, one row of the board after the other
+    fn boxes(shape: Shape) -> String {
+        (0..shape.cells()).map(|cell| char::from_digit(shape.box_of(cell) as u32, 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn box_of_rectangular_boxes() {
+        assert_eq!(boxes(Shape::new(2, 3).unwrap()), [
+            "000111", "000111",
+            "222333", "222333",
+            "444555", "444555"
+        ].concat());
+
+        assert_eq!(boxes(Shape::new(3, 4).unwrap()), [
+            "000011112222", "000011112222", "000011112222",
+            "333344445555", "333344445555", "333344445555",
+            "666677778888", "666677778888", "666677778888",
+            "9999aaaabbbb", "9999aaaabbbb", "9999aaaabbbb"
+        ].concat());
+    }
+
+    #[test]
+    fn cells_of_all_shapes() {
+        for shape in Shape::all() {
+            assert_eq!(shape.row_of(shape.cells() - 1), shape.size() - 1);
+            assert_eq!(shape.col_of(shape.cells() - 1), shape.size() - 1);
+            assert_eq!(shape.box_of(shape.cells() - 1), shape.size() - 1);
+            assert_eq!(shape.full_mask().count_ones() as usize, shape.size());
+        }
+    }
+}
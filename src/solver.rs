@@ -0,0 +1,247 @@
+use crate::backtrack::{Backtracker, SolveStats};
+use crate::dlx::Dlx;
+use crate::shape::Shape;
+use crate::technique::{Candidates, Deduction, Technique};
+
+//  Common interface of the solving engines, grids are the cells in row-major
+//  order with 0 for empty cells.
+pub trait Solver {
+    fn name(&self) -> &'static str;
+
+    // The completed grid, None when the engine cannot complete it
+    fn solve(&mut self, shape: Shape, grid: &[usize]) -> Option<Vec<usize>>;
+
+    // Count the solutions, stopping as soon as `limit` of them have been found
+    fn count_solutions(&mut self, shape: Shape, grid: &[usize], limit: usize) -> usize;
+
+    // The next deduction from a candidate grid, None when there is none
+    fn step(&mut self, candidates: &Candidates) -> Option<Deduction>;
+
+    // Search statistics of the last call, for engines that keep them
+    fn stats(&self) -> Option<SolveStats> {
+        None
+    }
+}
+
+pub const SOLVERS: [&str; 3] = ["backtrack", "dlx", "techniques"];
+
+// Largest board the backtracker finishes on, it gets lost in the sparse
+// grids of larger ones
+const MAX_BACKTRACK_SIZE: usize = 16;
+
+// The backtracker keeps statistics of its search, larger boards are left to
+// the exact cover search
+pub fn default_for(shape: Shape) -> Box<dyn Solver> {
+    match shape.size() > MAX_BACKTRACK_SIZE {
+        false => Box::new(BacktrackSolver::default()),
+        true => Box::new(DlxSolver)
+    }
+}
+
+pub fn by_name(name: &str) -> Option<Box<dyn Solver>> {
+    match name {
+        "backtrack" => Some(Box::new(BacktrackSolver::default())),
+        "dlx" => Some(Box::new(DlxSolver)),
+        "techniques" => Some(Box::new(TechniqueSolver)),
+        _ => None
+    }
+}
+
+// A search engine can only step by taking a value from its solution, the
+// empty cell with the fewest candidates is filled in first
+fn guess(candidates: &Candidates, solution: Option<Vec<usize>>) -> Option<Deduction> {
+    let solution = solution?;
+
+    let cell = (0..candidates.shape().cells())
+        .filter(|cell| candidates.value(*cell) == 0)
+        .min_by_key(|cell| candidates.mask(*cell).count_ones())?;
+
+    Some(Deduction {
+        technique: Technique::Guess,
+        placement: Some((cell, solution[cell])),
+        eliminations: Vec::new(),
+        cells: vec![cell],
+        values: vec![solution[cell]],
+        houses: Vec::new(),
+        cover: Vec::new()
+    })
+}
+
+fn grid_of(candidates: &Candidates) -> Vec<usize> {
+    (0..candidates.shape().cells()).map(|cell| candidates.value(cell)).collect()
+}
+
+//  Depth first search with bitmasks, keeps the statistics of the last search
+#[derive(Debug, Clone, Default)]
+pub struct BacktrackSolver {
+    stats: SolveStats
+}
+
+impl Solver for BacktrackSolver {
+    fn name(&self) -> &'static str {
+        "backtrack"
+    }
+
+    fn solve(&mut self, shape: Shape, grid: &[usize]) -> Option<Vec<usize>> {
+        let mut backtracker = Backtracker::new(shape, grid);
+        let solved = backtracker.solve();
+        self.stats = backtracker.stats();
+
+        match solved {
+            false => None,
+            true => Some(backtracker.cells())
+        }
+    }
+
+    fn count_solutions(&mut self, shape: Shape, grid: &[usize], limit: usize) -> usize {
+        let mut backtracker = Backtracker::new(shape, grid);
+        let count = backtracker.count_solutions(limit);
+        self.stats = backtracker.stats();
+
+        count
+    }
+
+    fn step(&mut self, candidates: &Candidates) -> Option<Deduction> {
+        guess(candidates, self.solve(candidates.shape(), &grid_of(candidates)))
+    }
+
+    fn stats(&self) -> Option<SolveStats> {
+        Some(self.stats)
+    }
+}
+
+//  Algorithm X on dancing links
+#[derive(Debug, Clone, Default)]
+pub struct DlxSolver;
+
+impl Solver for DlxSolver {
+    fn name(&self) -> &'static str {
+        "dlx"
+    }
+
+    fn solve(&mut self, shape: Shape, grid: &[usize]) -> Option<Vec<usize>> {
+        let mut dlx = Dlx::new(shape, grid);
+
+        match dlx.solve() {
+            false => None,
+            true => Some(dlx.cells())
+        }
+    }
+
+    fn count_solutions(&mut self, shape: Shape, grid: &[usize], limit: usize) -> usize {
+        Dlx::new(shape, grid).count_solutions(limit)
+    }
+
+    fn step(&mut self, candidates: &Candidates) -> Option<Deduction> {
+        guess(candidates, self.solve(candidates.shape(), &grid_of(candidates)))
+    }
+}
+
+//  The human techniques, simplest first. Solving stops when no technique
+//  applies, counting is left to the exact cover search.
+#[derive(Debug, Clone, Default)]
+pub struct TechniqueSolver;
+
+impl Solver for TechniqueSolver {
+    fn name(&self) -> &'static str {
+        "techniques"
+    }
+
+    fn solve(&mut self, shape: Shape, grid: &[usize]) -> Option<Vec<usize>> {
+        let mut candidates = Candidates::new(shape, grid);
+        candidates.set_unique_solution(self.count_solutions(shape, grid, 2) == 1);
+
+        while !candidates.is_solved() {
+            let deduction = self.step(&candidates)?;
+            candidates.apply(&deduction);
+        }
+
+        Some(grid_of(&candidates))
+    }
+
+    fn count_solutions(&mut self, shape: Shape, grid: &[usize], limit: usize) -> usize {
+        DlxSolver.count_solutions(shape, grid, limit)
+    }
+
+    fn step(&mut self, candidates: &Candidates) -> Option<Deduction> {
+        candidates.find_deduction()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(line: &str) -> Vec<usize> {
+        line.chars().map(|symbol| symbol.to_digit(10).unwrap_or(0) as usize).collect()
+    }
+
+    fn solvers() -> Vec<Box<dyn Solver>> {
+        SOLVERS.iter().map(|name| by_name(name).unwrap()).collect()
+    }
+
+    // Every house of a completed grid holds each digit once
+    fn is_solution(shape: Shape, puzzle: &[usize], solution: &[usize]) -> bool {
+        (0..shape.cells()).all(|cell| puzzle[cell] == 0 || puzzle[cell] == solution[cell])
+            && (0..shape.cells()).all(|cell| (0..shape.cells())
+                .filter(|other| *other != cell && solution[*other] == solution[cell])
+                .all(|other| shape.row_of(other) != shape.row_of(cell)
+                    && shape.col_of(other) != shape.col_of(cell)
+                    && shape.box_of(other) != shape.box_of(cell)))
+    }
+
+    const UNIQUE: &str = "1.5...9...9....3826....27.5..96.....2....5......1.7...5....327...6.5...4...8.....";
+    // The unique puzzle without its clue at r1c3, 5 solutions
+    const SEVERAL: &str = "1.....9...9....3826....27.5..96.....2....5......1.7...5....327...6.5...4...8.....";
+    // r1c9 can only be 9, which column 9 already holds
+    const NO_SOLUTION: &str = "12345678.........9...............................................................";
+    const CONFLICT: &str = "1.5...9.5.9....3826....27.5..96.....2....5......1.7...5....327...6.5...4...8.....";
+
+    #[test]
+    fn solvers_agree_on_unique_puzzle() {
+        let puzzle = grid(UNIQUE);
+        let solution = DlxSolver.solve(Shape::classic(), &puzzle).unwrap();
+        assert!(is_solution(Shape::classic(), &puzzle, &solution));
+
+        for mut solver in solvers() {
+            assert_eq!(solver.solve(Shape::classic(), &puzzle), Some(solution.clone()), "{}", solver.name());
+            assert_eq!(solver.count_solutions(Shape::classic(), &puzzle, 2), 1, "{}", solver.name());
+        }
+    }
+
+    #[test]
+    fn solvers_agree_on_several_solutions() {
+        let puzzle = grid(SEVERAL);
+
+        for mut solver in solvers() {
+            assert_eq!(solver.count_solutions(Shape::classic(), &puzzle, 100), 5, "{}", solver.name());
+        }
+
+        // The search engines pick any of the solutions, the techniques cannot pick one
+        for mut solver in [Box::new(BacktrackSolver::default()) as Box<dyn Solver>, Box::new(DlxSolver)] {
+            let solution = solver.solve(Shape::classic(), &puzzle).unwrap();
+            assert!(is_solution(Shape::classic(), &puzzle, &solution), "{}", solver.name());
+        }
+        assert_eq!(TechniqueSolver.solve(Shape::classic(), &puzzle), None);
+    }
+
+    #[test]
+    fn solvers_agree_on_no_solution() {
+        for puzzle in [grid(NO_SOLUTION), grid(CONFLICT)] {
+            for mut solver in solvers() {
+                assert_eq!(solver.solve(Shape::classic(), &puzzle), None, "{}", solver.name());
+                assert_eq!(solver.count_solutions(Shape::classic(), &puzzle, 2), 0, "{}", solver.name());
+            }
+        }
+    }
+
+    #[test]
+    fn count_stops_at_limit() {
+        let empty = vec![0; 81];
+
+        for mut solver in solvers() {
+            assert_eq!(solver.count_solutions(Shape::classic(), &empty, 1), 1, "{}", solver.name());
+            assert_eq!(solver.count_solutions(Shape::classic(), &empty, 5), 5, "{}", solver.name());
+        }
+    }
+}
@@ -0,0 +1,1719 @@
+use std::{collections::{HashMap, VecDeque}, fmt::Display};
+
+use itertools::Itertools;
+use serde::{Serialize, Serializer};
+
+use crate::cursor::Cursor;
+use crate::shape::Shape;
+
+//  Cells are numbered 0 to 80 in row-major order, candidates of a cell are
+//  stored as a bitmask where bit 1 to 9 is set when the digit is possible.
+
+pub fn row_of(cell: usize) -> usize {
+    cell / 9
+}
+
+pub fn col_of(cell: usize) -> usize {
+    cell % 9
+}
+
+pub fn block_of(cell: usize) -> usize {
+    (cell / 27) * 3 + (cell % 9) / 3
+}
+
+// Two different cells sharing a row, column or block
+pub fn sees(first: usize, second: usize) -> bool {
+    first != second && (row_of(first) == row_of(second)
+        || col_of(first) == col_of(second)
+        || block_of(first) == block_of(second))
+}
+
+pub fn digits(mask: u32) -> impl Iterator<Item = usize> + Clone {
+    (1..=Shape::MAX_SIZE).filter(move |value| mask & (1 << value) != 0)
+}
+
+// Constructor of a row or column house from its number
+type Line = fn(usize) -> House;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum House {
+    Row(usize),
+    Col(usize),
+    Block(usize)
+}
+
+impl House {
+    pub fn all() -> Vec<House> {
+        (0..9).map(House::Block)
+            .chain((0..9).map(House::Row))
+            .chain((0..9).map(House::Col))
+            .collect()
+    }
+
+    pub fn cells(&self) -> [usize; 9] {
+        let mut cells = [0; 9];
+
+        for (i, cell) in cells.iter_mut().enumerate() {
+            *cell = match self {
+                House::Row(row) => row * 9 + i,
+                House::Col(col) => i * 9 + col,
+                House::Block(block) => (block / 3) * 27 + (block % 3) * 3 + (i / 3) * 9 + i % 3
+            }
+        }
+
+        cells
+    }
+
+    // Houses of a board of any shape, blocks first like `all`
+    pub fn all_in(shape: Shape) -> Vec<House> {
+        (0..shape.size()).map(House::Block)
+            .chain((0..shape.size()).map(House::Row))
+            .chain((0..shape.size()).map(House::Col))
+            .collect()
+    }
+
+    pub fn cells_in(&self, shape: Shape) -> Vec<usize> {
+        let size = shape.size();
+
+        (0..size).map(|i| match self {
+            House::Row(row) => row * size + i,
+            House::Col(col) => i * size + col,
+            House::Block(block) => {
+                let (top, left) = ((block / shape.box_rows()) * shape.box_rows(), (block % shape.box_rows()) * shape.box_cols());
+                (top + i / shape.box_cols()) * size + left + i % shape.box_cols()
+            }
+        }).collect()
+    }
+}
+
+// Houses are written the way they are shown to the player, like "row 4"
+impl Serialize for House {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl Display for House {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            House::Row(row) => write!(f, "row {}", row + 1),
+            House::Col(col) => write!(f, "column {}", col + 1),
+            House::Block(block) => write!(f, "block {}", block + 1)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+    Diabolical
+}
+
+impl Difficulty {
+    pub fn all() -> [Difficulty; 5] {
+        [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard, Difficulty::Expert, Difficulty::Diabolical]
+    }
+
+    // The difficulty is a band of scores, so both always rank puzzles alike
+    pub fn from_score(score: f32) -> Difficulty {
+        match score {
+            score if score < 2.0 => Difficulty::Easy,
+            score if score < 3.5 => Difficulty::Medium,
+            score if score < 4.1 => Difficulty::Hard,
+            score if score < 6.0 => Difficulty::Expert,
+            _ => Difficulty::Diabolical
+        }
+    }
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "Easy"),
+            Difficulty::Medium => write!(f, "Medium"),
+            Difficulty::Hard => write!(f, "Hard"),
+            Difficulty::Expert => write!(f, "Expert"),
+            Difficulty::Diabolical => write!(f, "Diabolical")
+        }
+    }
+}
+
+//  Techniques are listed from the simplest to the hardest, which is also
+//  the order they are tried in when looking for the next deduction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum Technique {
+    HiddenSingle,
+    NakedSingle,
+    Pointing,
+    Claiming,
+    NakedPair,
+    XWing,
+    HiddenPair,
+    NakedTriple,
+    Swordfish,
+    HiddenTriple,
+    XYWing,
+    XYZWing,
+    UniqueRectangle1,
+    UniqueRectangle2,
+    UniqueRectangle3,
+    UniqueRectangle4,
+    WWing,
+    SimpleColoring,
+    NakedQuad,
+    Jellyfish,
+    HiddenQuad,
+    BugPlusOne,
+    XChain,
+    XYChain,
+    // Not a technique, a value taken from the solution found by a search
+    Guess
+}
+
+impl Technique {
+    pub fn difficulty(&self) -> Difficulty {
+        Difficulty::from_score(self.score())
+    }
+
+    // Numeric rating of a single step, on the same scale as the Sudoku
+    // Explainer ratings so scores can be compared with other collections
+    pub fn score(&self) -> f32 {
+        match self {
+            Technique::HiddenSingle => 1.5,
+            Technique::NakedSingle => 2.3,
+            Technique::Pointing => 2.6,
+            Technique::Claiming => 2.8,
+            Technique::NakedPair => 3.0,
+            Technique::XWing => 3.2,
+            Technique::HiddenPair => 3.4,
+            Technique::NakedTriple => 3.6,
+            Technique::Swordfish => 3.8,
+            Technique::HiddenTriple => 4.0,
+            Technique::XYWing => 4.2,
+            Technique::XYZWing => 4.4,
+            // The types are looked for together in each rectangle, so they share a score
+            Technique::UniqueRectangle1 => 4.5,
+            Technique::UniqueRectangle2 => 4.5,
+            Technique::UniqueRectangle3 => 4.5,
+            Technique::UniqueRectangle4 => 4.5,
+            Technique::WWing => 4.6,
+            Technique::SimpleColoring => 4.7,
+            Technique::NakedQuad => 5.0,
+            Technique::Jellyfish => 5.2,
+            Technique::HiddenQuad => 5.4,
+            Technique::BugPlusOne => 5.6,
+            Technique::XChain => 6.6,
+            Technique::XYChain => 6.8,
+            Technique::Guess => 10.0
+        }
+    }
+
+    fn fish(size: usize) -> Technique {
+        match size {
+            2 => Technique::XWing,
+            3 => Technique::Swordfish,
+            _ => Technique::Jellyfish
+        }
+    }
+
+    fn naked_subset(size: usize) -> Technique {
+        match size {
+            2 => Technique::NakedPair,
+            3 => Technique::NakedTriple,
+            _ => Technique::NakedQuad
+        }
+    }
+
+    fn hidden_subset(size: usize) -> Technique {
+        match size {
+            2 => Technique::HiddenPair,
+            3 => Technique::HiddenTriple,
+            _ => Technique::HiddenQuad
+        }
+    }
+}
+
+impl Display for Technique {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Technique::HiddenSingle => write!(f, "Hidden Single"),
+            Technique::NakedSingle => write!(f, "Naked Single"),
+            Technique::Pointing => write!(f, "Pointing"),
+            Technique::Claiming => write!(f, "Box/Line Reduction"),
+            Technique::NakedPair => write!(f, "Naked Pair"),
+            Technique::XWing => write!(f, "X-Wing"),
+            Technique::HiddenPair => write!(f, "Hidden Pair"),
+            Technique::NakedTriple => write!(f, "Naked Triple"),
+            Technique::Swordfish => write!(f, "Swordfish"),
+            Technique::HiddenTriple => write!(f, "Hidden Triple"),
+            Technique::XYWing => write!(f, "XY-Wing"),
+            Technique::XYZWing => write!(f, "XYZ-Wing"),
+            Technique::UniqueRectangle1 => write!(f, "Unique Rectangle Type 1"),
+            Technique::UniqueRectangle2 => write!(f, "Unique Rectangle Type 2"),
+            Technique::UniqueRectangle3 => write!(f, "Unique Rectangle Type 3"),
+            Technique::UniqueRectangle4 => write!(f, "Unique Rectangle Type 4"),
+            Technique::WWing => write!(f, "W-Wing"),
+            Technique::SimpleColoring => write!(f, "Simple Coloring"),
+            Technique::NakedQuad => write!(f, "Naked Quad"),
+            Technique::Jellyfish => write!(f, "Jellyfish"),
+            Technique::HiddenQuad => write!(f, "Hidden Quad"),
+            Technique::BugPlusOne => write!(f, "BUG+1"),
+            Technique::XChain => write!(f, "X-Chain"),
+            Technique::XYChain => write!(f, "XY-Chain"),
+            Technique::Guess => write!(f, "Guess")
+        }
+    }
+}
+
+//  A single step of the technique solver. It either places a value or
+//  removes candidates, the cells, values and houses are the pattern that
+//  was found and justify the step. Fish use `houses` as their base sets
+//  and `cover` as their cover sets, other techniques leave `cover` empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deduction {
+    pub technique: Technique,
+    pub placement: Option<(usize, usize)>,
+    pub eliminations: Vec<(usize, usize)>,
+    pub cells: Vec<usize>,
+    pub values: Vec<usize>,
+    pub houses: Vec<House>,
+    pub cover: Vec<House>
+}
+
+impl Deduction {
+    // Eliminations grouped by value, "3 from r1c2, r1c9; 7 from r1c9"
+    fn eliminations_text(&self, shape: Shape) -> String {
+        self.eliminations.iter()
+            .map(|(_, value)| *value)
+            .sorted()
+            .dedup()
+            .map(|value| {
+                let cells = self.eliminations.iter()
+                    .filter(|(_, eliminated)| *eliminated == value)
+                    .map(|(cell, _)| shape.cell_name(*cell))
+                    .join(", ");
+                format!("{value} from {cells}")
+            })
+            .join("; ")
+    }
+
+    // The step as shown to the player, with the cells named on a board of
+    // the given shape
+    pub fn explanation(&self, shape: Shape) -> String {
+        let cell_name = |cell: usize| shape.cell_name(cell);
+        let cells = self.cells.iter().map(|cell| cell_name(*cell)).join(", ");
+        let values = self.values.iter().join(", ");
+        let houses: Vec<String> = self.houses.iter().map(|house| house.to_string()).collect();
+        let eliminations = self.eliminations_text(shape);
+
+        let text = match self.technique {
+            Technique::HiddenSingle => format!("{values} can only go in {cells} in {}", houses[0]),
+            Technique::NakedSingle => format!("{cells} can only be {values}"),
+            Technique::Pointing | Technique::Claiming => format!(
+                "{values} in {} is only possible in {}, so remove {}",
+                houses[0], houses[1], eliminations),
+            Technique::NakedPair | Technique::NakedTriple | Technique::NakedQuad => format!(
+                "{cells} only hold {values} in {}, so remove {}",
+                houses[0], eliminations),
+            Technique::HiddenPair | Technique::HiddenTriple | Technique::HiddenQuad => format!(
+                "{values} are only possible in {cells} in {}, so remove {}",
+                houses[0], eliminations),
+            Technique::XWing | Technique::Swordfish | Technique::Jellyfish => format!(
+                "{values} in {} is only possible in {}, so remove {}",
+                houses.join(", "), self.cover.iter().join(", "), eliminations),
+            Technique::XYWing => format!(
+                "{} is {} or {}, so either {} or {} is {}, remove {}",
+                cell_name(self.cells[0]), self.values[0], self.values[1],
+                cell_name(self.cells[1]), cell_name(self.cells[2]), self.values[2], eliminations),
+            Technique::XYZWing => format!(
+                "{cells} hold {values} between them and one of them is {}, so remove {}",
+                self.values[2], eliminations),
+            Technique::WWing => format!(
+                "{} and {} hold {values} and the {} in {} links them, so one of them is {}, remove {}",
+                cell_name(self.cells[0]), cell_name(self.cells[3]), self.values[0], houses[0],
+                self.values[1], eliminations),
+            Technique::SimpleColoring => format!(
+                "the strong links of {values} through {cells} split into two colours, so remove {}",
+                eliminations),
+            Technique::UniqueRectangle1 => format!(
+                "{cells} would be a deadly pattern on {} and {}, so remove {}",
+                self.values[0], self.values[1], eliminations),
+            Technique::UniqueRectangle2 => format!(
+                "{cells} would be a deadly pattern on {} and {} unless one of them is {}, so remove {}",
+                self.values[0], self.values[1], self.values[2], eliminations),
+            Technique::UniqueRectangle3 => format!(
+                "{cells} would be a deadly pattern on {} and {}, so the other candidates form a subset in {}, remove {}",
+                self.values[0], self.values[1], houses[0], eliminations),
+            Technique::UniqueRectangle4 => format!(
+                "{cells} would be a deadly pattern on {} and {}, and {} has to be in one of them in {}, so remove {}",
+                self.values[0], self.values[1], self.values[0], houses[0], eliminations),
+            Technique::BugPlusOne => format!(
+                "every other unsolved cell has two candidates, so {cells} must be {values} to avoid a deadly pattern"),
+            Technique::XChain | Technique::XYChain => format!(
+                "in the chain {} one of the ends is {values}, so remove {}",
+                self.cells.iter().map(|cell| cell_name(*cell)).join(" - "), eliminations),
+            Technique::Guess => format!("{cells} is {values} in the solution")
+        };
+
+        format!("{}: {text}", self.technique)
+    }
+}
+
+//  A deduction in board coordinates, with rows and columns numbered from 1
+//  like the rest of the public API, and the explanation shown to the player.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Hint {
+    pub technique: Technique,
+    pub placement: Option<(Cursor, usize)>,
+    pub eliminations: Vec<(Cursor, usize)>,
+    pub cells: Vec<Cursor>,
+    pub values: Vec<usize>,
+    pub houses: Vec<House>,
+    pub cover: Vec<House>,
+    pub explanation: String
+}
+
+impl Hint {
+    // The deduction on a board of the given shape
+    pub fn new(deduction: &Deduction, shape: Shape) -> Hint {
+        Hint {
+            technique: deduction.technique,
+            placement: deduction.placement.map(|(cell, value)| (shape.cursor_of(cell), value)),
+            eliminations: deduction.eliminations.iter().map(|(cell, value)| (shape.cursor_of(*cell), *value)).collect(),
+            cells: deduction.cells.iter().map(|cell| shape.cursor_of(*cell)).collect(),
+            values: deduction.values.clone(),
+            houses: deduction.houses.clone(),
+            cover: deduction.cover.clone(),
+            explanation: deduction.explanation(shape)
+        }
+    }
+
+    // Where the hint happens, the placed cell or the first cell of the pattern
+    pub fn cursor(&self) -> Cursor {
+        match self.placement {
+            Some((cursor, _)) => cursor,
+            None => self.cells.first().copied().unwrap_or_default()
+        }
+    }
+
+    // The house to look at, a pattern without one of its own is found by
+    // the row of its cell so the cell itself is not given away
+    fn house(&self) -> House {
+        match self.houses.first() {
+            Some(house) => *house,
+            None => House::Row(self.cursor().row - 1)
+        }
+    }
+
+    // First hint level, only where to look
+    pub fn area(&self) -> String {
+        format!("Look at {}", self.house())
+    }
+
+    // Second hint level, the technique and values without the answer
+    pub fn outline(&self) -> String {
+        let name = self.technique.to_string();
+        let article = match name.starts_with('X') {
+            true => "an",
+            false => "a"
+        };
+        let values = self.values.iter().join(", ");
+
+        format!("There is {article} {name} for {values} in {}", self.house())
+    }
+}
+
+impl Display for Hint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.explanation)
+    }
+}
+
+//  Result of looking for the next step, a puzzle that is not solved yet can
+//  still be stuck when none of the techniques applies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepOutcome {
+    Deduced(Hint),
+    Solved,
+    Stuck
+}
+
+//  Every step the techniques take from a position, in order. The path ends
+//  early when no technique applies and the rest can only be found by guessing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SolvePath {
+    pub steps: Vec<Hint>,
+    pub solved: bool
+}
+
+impl Display for SolvePath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (number, step) in self.steps.iter().enumerate() {
+            writeln!(f, "{}. {}", number + 1, step.explanation)?;
+        }
+
+        match self.solved {
+            true => write!(f, "Solved in {} steps", self.steps.len()),
+            false => write!(f, "Stuck after {} steps, no technique applies", self.steps.len())
+        }
+    }
+}
+
+//  Candidates of every cell on a board of any shape. The singles, subsets,
+//  intersections and fish only walk houses and work on every board, the
+//  wings, chains, coloring and uniqueness techniques only know the classic
+//  9x9 board. Uniqueness techniques are only used once the puzzle is known
+//  to have a single solution, see `set_unique_solution`.
+#[derive(Debug, Clone)]
+pub struct Candidates {
+    shape: Shape,
+    values: Vec<usize>,
+    masks: Vec<u32>,
+    unique_solution: bool
+}
+
+impl Candidates {
+    pub fn new(shape: Shape, grid: &[usize]) -> Candidates {
+        let mut candidates = Candidates {
+            shape,
+            values: vec![0; shape.cells()],
+            masks: vec![shape.full_mask(); shape.cells()],
+            unique_solution: false
+        };
+
+        for (cell, value) in grid.iter().copied().enumerate() {
+            if value != 0 {
+                candidates.place(cell, value);
+            }
+        }
+
+        candidates
+    }
+
+    pub fn shape(&self) -> Shape {
+        self.shape
+    }
+
+    pub fn value(&self, cell: usize) -> usize {
+        self.values[cell]
+    }
+
+    pub fn mask(&self, cell: usize) -> u32 {
+        self.masks[cell]
+    }
+
+    pub fn set_unique_solution(&mut self, unique_solution: bool) {
+        self.unique_solution = unique_solution
+    }
+
+    pub fn has_unique_solution(&self) -> bool {
+        self.unique_solution
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.values.iter().all(|value| *value != 0)
+    }
+
+    pub fn place(&mut self, cell: usize, value: usize) {
+        self.values[cell] = value;
+        self.masks[cell] = 0;
+
+        // Remove the value from every cell sharing a house with the cell
+        let shape = self.shape;
+        for house in [House::Row(shape.row_of(cell)), House::Col(shape.col_of(cell)), House::Block(shape.box_of(cell))] {
+            for peer in house.cells_in(shape) {
+                self.masks[peer] &= !(1 << value);
+            }
+        }
+    }
+
+    pub fn eliminate(&mut self, cell: usize, value: usize) {
+        self.masks[cell] &= !(1 << value);
+    }
+
+    pub fn apply(&mut self, deduction: &Deduction) {
+        if let Some((cell, value)) = deduction.placement {
+            self.place(cell, value);
+        }
+
+        for (cell, value) in &deduction.eliminations {
+            self.eliminate(*cell, *value);
+        }
+    }
+
+    // The simplest deduction available in the grid
+    pub fn find_deduction(&self) -> Option<Deduction> {
+        let classic = |technique: fn(&Candidates) -> Option<Deduction>| match self.shape.is_classic() {
+            false => None,
+            true => technique(self)
+        };
+
+        self.hidden_single()
+            .or_else(|| self.naked_single())
+            .or_else(|| self.pointing())
+            .or_else(|| self.claiming())
+            .or_else(|| self.naked_subset(2))
+            .or_else(|| self.fish(2))
+            .or_else(|| self.hidden_subset(2))
+            .or_else(|| self.naked_subset(3))
+            .or_else(|| self.fish(3))
+            .or_else(|| self.hidden_subset(3))
+            .or_else(|| classic(Candidates::xy_wing))
+            .or_else(|| classic(Candidates::xyz_wing))
+            .or_else(|| classic(Candidates::unique_rectangle))
+            .or_else(|| classic(Candidates::w_wing))
+            .or_else(|| classic(Candidates::simple_coloring))
+            .or_else(|| self.naked_subset(4))
+            .or_else(|| self.fish(4))
+            .or_else(|| self.hidden_subset(4))
+            .or_else(|| classic(Candidates::bug_plus_one))
+            .or_else(|| classic(Candidates::x_chain))
+            .or_else(|| classic(Candidates::xy_chain))
+    }
+
+    fn unsolved(&self, house: House) -> Vec<usize> {
+        house.cells_in(self.shape).into_iter().filter(|cell| self.values[*cell] == 0).collect()
+    }
+
+    // Cells holding the value as a candidate that see every one of `cells`
+    fn common_peers(&self, cells: &[usize], value: usize) -> Vec<usize> {
+        (0..81)
+            .filter(|cell| self.masks[*cell] & (1 << value) != 0)
+            .filter(|cell| cells.iter().all(|other| sees(*cell, *other)))
+            .collect()
+    }
+
+    fn bivalue_cells(&self) -> Vec<usize> {
+        (0..81).filter(|cell| self.masks[*cell].count_ones() == 2).collect()
+    }
+
+    // Pairs of cells that are the only two places of the value in a house
+    fn strong_links(&self, value: usize) -> Vec<(usize, usize, House)> {
+        House::all_in(self.shape).into_iter()
+            .filter_map(|house| match self.places(house, value)[..] {
+                [first, second] => Some((first, second, house)),
+                _ => None
+            })
+            .collect()
+    }
+
+    // Cells of the house where the value is still a candidate
+    fn places(&self, house: House, value: usize) -> Vec<usize> {
+        house.cells_in(self.shape).into_iter().filter(|cell| self.masks[*cell] & (1 << value) != 0).collect()
+    }
+
+    fn hidden_single(&self) -> Option<Deduction> {
+        for house in House::all_in(self.shape) {
+            for value in 1..=self.shape.size() {
+                if let [cell] = self.places(house, value)[..] {
+                    return Some(Deduction {
+                        technique: Technique::HiddenSingle,
+                        placement: Some((cell, value)),
+                        eliminations: Vec::new(),
+                        cells: vec![cell],
+                        values: vec![value],
+                        houses: vec![house],
+                        cover: Vec::new()
+                    })
+                }
+            }
+        }
+
+        None
+    }
+
+    fn naked_single(&self) -> Option<Deduction> {
+        for cell in 0..self.shape.cells() {
+            if self.masks[cell].count_ones() == 1 {
+                let value = self.masks[cell].trailing_zeros() as usize;
+
+                return Some(Deduction {
+                    technique: Technique::NakedSingle,
+                    placement: Some((cell, value)),
+                    eliminations: Vec::new(),
+                    cells: vec![cell],
+                    values: vec![value],
+                    houses: Vec::new(),
+                    cover: Vec::new()
+                })
+            }
+        }
+
+        None
+    }
+
+    // A value only possible in one row or column of a block can be removed
+    // from the rest of that row or column
+    fn pointing(&self) -> Option<Deduction> {
+        let shape = self.shape;
+
+        for block in 0..shape.size() {
+            for value in 1..=shape.size() {
+                let cells = self.places(House::Block(block), value);
+                if cells.len() < 2 {
+                    continue;
+                }
+
+                let lines = [
+                    (House::Row(shape.row_of(cells[0])), cells.iter().all(|cell| shape.row_of(*cell) == shape.row_of(cells[0]))),
+                    (House::Col(shape.col_of(cells[0])), cells.iter().all(|cell| shape.col_of(*cell) == shape.col_of(cells[0])))
+                ];
+
+                for (line, aligned) in lines {
+                    if !aligned {
+                        continue;
+                    }
+
+                    let eliminations: Vec<(usize, usize)> = self.places(line, value).into_iter()
+                        .filter(|cell| shape.box_of(*cell) != block)
+                        .map(|cell| (cell, value))
+                        .collect();
+
+                    if !eliminations.is_empty() {
+                        return Some(Deduction {
+                            technique: Technique::Pointing,
+                            placement: None,
+                            eliminations,
+                            cells,
+                            values: vec![value],
+                            houses: vec![House::Block(block), line],
+                            cover: Vec::new()
+                        })
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    // A value only possible inside one block of a row or column can be
+    // removed from the rest of that block
+    fn claiming(&self) -> Option<Deduction> {
+        let shape = self.shape;
+        let lines = (0..shape.size()).map(House::Row).chain((0..shape.size()).map(House::Col));
+
+        for line in lines {
+            for value in 1..=shape.size() {
+                let cells = self.places(line, value);
+                if cells.len() < 2 || cells.iter().any(|cell| shape.box_of(*cell) != shape.box_of(cells[0])) {
+                    continue;
+                }
+
+                let block = House::Block(shape.box_of(cells[0]));
+                let eliminations: Vec<(usize, usize)> = self.places(block, value).into_iter()
+                    .filter(|cell| !cells.contains(cell))
+                    .map(|cell| (cell, value))
+                    .collect();
+
+                if !eliminations.is_empty() {
+                    return Some(Deduction {
+                        technique: Technique::Claiming,
+                        placement: None,
+                        eliminations,
+                        cells,
+                        values: vec![value],
+                        houses: vec![line, block],
+                        cover: Vec::new()
+                    })
+                }
+            }
+        }
+
+        None
+    }
+
+    // `size` cells of a house holding only `size` candidates between them,
+    // those candidates can be removed from the rest of the house
+    fn naked_subset(&self, size: usize) -> Option<Deduction> {
+        for house in House::all_in(self.shape) {
+            let unsolved = self.unsolved(house);
+            let possible = unsolved.iter().copied().filter(|cell| {
+                (2..=size as u32).contains(&self.masks[*cell].count_ones())
+            });
+
+            for cells in possible.combinations(size) {
+                let mask = cells.iter().fold(0, |mask, cell| mask | self.masks[*cell]);
+                if mask.count_ones() as usize != size {
+                    continue;
+                }
+
+                let eliminations: Vec<(usize, usize)> = unsolved.iter()
+                    .filter(|cell| !cells.contains(cell))
+                    .flat_map(|cell| digits(self.masks[*cell] & mask).map(move |value| (*cell, value)))
+                    .collect();
+
+                if !eliminations.is_empty() {
+                    return Some(Deduction {
+                        technique: Technique::naked_subset(size),
+                        placement: None,
+                        eliminations,
+                        cells,
+                        values: digits(mask).collect(),
+                        houses: vec![house],
+                        cover: Vec::new()
+                    })
+                }
+            }
+        }
+
+        None
+    }
+
+    // `size` candidates only possible in `size` cells of a house, every
+    // other candidate can be removed from those cells
+    fn hidden_subset(&self, size: usize) -> Option<Deduction> {
+        for house in House::all_in(self.shape) {
+            let unsolved = self.unsolved(house);
+            let open = unsolved.iter().fold(0, |mask, cell| mask | self.masks[*cell]);
+
+            for values in digits(open).combinations(size) {
+                let mask = values.iter().fold(0u32, |mask, value| mask | (1 << value));
+
+                let cells: Vec<usize> = unsolved.iter().copied()
+                    .filter(|cell| self.masks[*cell] & mask != 0)
+                    .collect();
+                if cells.len() != size {
+                    continue;
+                }
+
+                let eliminations: Vec<(usize, usize)> = cells.iter()
+                    .flat_map(|cell| digits(self.masks[*cell] & !mask).map(move |value| (*cell, value)))
+                    .collect();
+
+                if !eliminations.is_empty() {
+                    return Some(Deduction {
+                        technique: Technique::hidden_subset(size),
+                        placement: None,
+                        eliminations,
+                        cells,
+                        values,
+                        houses: vec![house],
+                        cover: Vec::new()
+                    })
+                }
+            }
+        }
+
+        None
+    }
+
+    // A value limited to `size` cover columns across `size` base rows must
+    // take those columns in the base rows, and the same with rows and
+    // columns swapped
+    fn fish(&self, size: usize) -> Option<Deduction> {
+        for rows_as_base in [true, false] {
+            let (base_house, cover_house, cover_of): (Line, Line, fn(&Shape, usize) -> usize) = match rows_as_base {
+                true => (House::Row, House::Col, Shape::col_of),
+                false => (House::Col, House::Row, Shape::row_of)
+            };
+
+            for value in 1..=self.shape.size() {
+                let lines: Vec<(usize, u32)> = (0..self.shape.size()).filter_map(|line| {
+                    let covers = self.places(base_house(line), value).into_iter()
+                        .fold(0u32, |mask, cell| mask | (1 << cover_of(&self.shape, cell)));
+                    (2..=size as u32).contains(&covers.count_ones()).then_some((line, covers))
+                }).collect();
+
+                for base in lines.into_iter().combinations(size) {
+                    let covers = base.iter().fold(0u32, |mask, (_, covers)| mask | covers);
+                    if covers.count_ones() as usize != size {
+                        continue;
+                    }
+
+                    let base_lines: Vec<usize> = base.iter().map(|(line, _)| *line).collect();
+                    let cover_lines: Vec<usize> = (0..self.shape.size()).filter(|line| covers & (1 << line) != 0).collect();
+
+                    let mut cells = Vec::new();
+                    let mut eliminations = Vec::new();
+                    for line in &cover_lines {
+                        for cell in self.places(cover_house(*line), value) {
+                            match base_lines.iter().any(|base| base_house(*base).cells_in(self.shape).contains(&cell)) {
+                                true => cells.push(cell),
+                                false => eliminations.push((cell, value))
+                            }
+                        }
+                    }
+
+                    if !eliminations.is_empty() {
+                        cells.sort();
+                        return Some(Deduction {
+                            technique: Technique::fish(size),
+                            placement: None,
+                            eliminations,
+                            cells,
+                            values: vec![value],
+                            houses: base_lines.into_iter().map(base_house).collect(),
+                            cover: cover_lines.into_iter().map(cover_house).collect()
+                        })
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    // A bivalue pivot XY sees two bivalue pincers XZ and YZ, whatever the
+    // pivot is one pincer is Z
+    fn xy_wing(&self) -> Option<Deduction> {
+        let bivalue = self.bivalue_cells();
+
+        for &pivot in &bivalue {
+            let pivot_mask = self.masks[pivot];
+
+            for &first in bivalue.iter().filter(|cell| sees(pivot, **cell)) {
+                let shared = self.masks[first] & pivot_mask;
+                if shared.count_ones() != 1 {
+                    continue;
+                }
+
+                let x = shared.trailing_zeros() as usize;
+                let y = (pivot_mask & !shared).trailing_zeros() as usize;
+                let z = (self.masks[first] & !shared).trailing_zeros() as usize;
+
+                for &second in bivalue.iter().filter(|cell| sees(pivot, **cell) && **cell != first) {
+                    if self.masks[second] != (1 << y) | (1 << z) {
+                        continue;
+                    }
+
+                    let eliminations: Vec<(usize, usize)> = self.common_peers(&[first, second], z).into_iter()
+                        .map(|cell| (cell, z))
+                        .collect();
+
+                    if !eliminations.is_empty() {
+                        return Some(Deduction {
+                            technique: Technique::XYWing,
+                            placement: None,
+                            eliminations,
+                            cells: vec![pivot, first, second],
+                            values: vec![x, y, z],
+                            houses: Vec::new(),
+                            cover: Vec::new()
+                        })
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    // A pivot XYZ sees two bivalue pincers XZ and YZ, one of the three
+    // cells is Z
+    fn xyz_wing(&self) -> Option<Deduction> {
+        let bivalue = self.bivalue_cells();
+
+        for pivot in (0..81).filter(|cell| self.masks[*cell].count_ones() == 3) {
+            let pivot_mask = self.masks[pivot];
+            let pincers: Vec<usize> = bivalue.iter().copied()
+                .filter(|cell| sees(pivot, *cell) && self.masks[*cell] & !pivot_mask == 0)
+                .collect();
+
+            for (first, second) in pincers.iter().copied().tuple_combinations() {
+                let shared = self.masks[first] & self.masks[second];
+                if shared.count_ones() != 1 || self.masks[first] | self.masks[second] != pivot_mask {
+                    continue;
+                }
+
+                let z = shared.trailing_zeros() as usize;
+                let eliminations: Vec<(usize, usize)> = self.common_peers(&[pivot, first, second], z).into_iter()
+                    .map(|cell| (cell, z))
+                    .collect();
+
+                if !eliminations.is_empty() {
+                    let mut values: Vec<usize> = digits(pivot_mask & !shared).collect();
+                    values.push(z);
+
+                    return Some(Deduction {
+                        technique: Technique::XYZWing,
+                        placement: None,
+                        eliminations,
+                        cells: vec![pivot, first, second],
+                        values,
+                        houses: Vec::new(),
+                        cover: Vec::new()
+                    })
+                }
+            }
+        }
+
+        None
+    }
+
+    // Two XY cells that cannot see each other, joined by a strong link on X
+    // where each end sees one of them. One of the two cells must be Y.
+    fn w_wing(&self) -> Option<Deduction> {
+        let bivalue = self.bivalue_cells();
+
+        for (first, second) in bivalue.iter().copied().tuple_combinations() {
+            if self.masks[first] != self.masks[second] || sees(first, second) {
+                continue;
+            }
+
+            for x in digits(self.masks[first]) {
+                let y = (self.masks[first] & !(1 << x)).trailing_zeros() as usize;
+
+                for (start, end, house) in self.strong_links(x) {
+                    let linked = [(start, end), (end, start)].into_iter().find(|(near, far)| {
+                        ![first, second].contains(near) && ![first, second].contains(far)
+                            && sees(*near, first) && sees(*far, second)
+                    });
+
+                    let Some((near, far)) = linked else {
+                        continue;
+                    };
+
+                    let eliminations: Vec<(usize, usize)> = self.common_peers(&[first, second], y).into_iter()
+                        .map(|cell| (cell, y))
+                        .collect();
+
+                    if !eliminations.is_empty() {
+                        return Some(Deduction {
+                            technique: Technique::WWing,
+                            placement: None,
+                            eliminations,
+                            cells: vec![first, near, far, second],
+                            values: vec![x, y],
+                            houses: vec![house],
+                            cover: Vec::new()
+                        })
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    // Colour the cells joined by strong links on a single value in two
+    // alternating colours, exactly one colour holds the value. A colour
+    // seeing itself is false, and a cell seeing both colours is false.
+    fn simple_coloring(&self) -> Option<Deduction> {
+        for value in 1..=9 {
+            let links = self.strong_links(value);
+            let mut colours: [Option<bool>; 81] = [None; 81];
+
+            for &(root, _, _) in &links {
+                if colours[root].is_some() {
+                    continue;
+                }
+
+                // Colour the whole cluster of the root
+                let mut cluster = vec![root];
+                colours[root] = Some(true);
+                let mut index = 0;
+                while index < cluster.len() {
+                    let cell = cluster[index];
+                    for &(first, second, _) in &links {
+                        let next = match (first == cell, second == cell) {
+                            (true, _) => second,
+                            (_, true) => first,
+                            _ => continue
+                        };
+                        if colours[next].is_none() {
+                            colours[next] = colours[cell].map(|colour| !colour);
+                            cluster.push(next);
+                        }
+                    }
+                    index += 1;
+                }
+
+                let colour_of = |colour: bool| -> Vec<usize> {
+                    cluster.iter().copied().filter(|cell| colours[*cell] == Some(colour)).collect()
+                };
+
+                let mut eliminations: Vec<(usize, usize)> = Vec::new();
+                for colour in [true, false] {
+                    let cells = colour_of(colour);
+                    if cells.iter().tuple_combinations().any(|(first, second)| sees(*first, *second)) {
+                        eliminations = cells.into_iter().map(|cell| (cell, value)).collect();
+                        break;
+                    }
+                }
+
+                if eliminations.is_empty() {
+                    let (on, off) = (colour_of(true), colour_of(false));
+                    eliminations = (0..81)
+                        .filter(|cell| self.masks[*cell] & (1 << value) != 0 && !cluster.contains(cell))
+                        .filter(|cell| on.iter().any(|other| sees(*cell, *other)) && off.iter().any(|other| sees(*cell, *other)))
+                        .map(|cell| (cell, value))
+                        .collect();
+                }
+
+                if !eliminations.is_empty() {
+                    cluster.sort();
+                    return Some(Deduction {
+                        technique: Technique::SimpleColoring,
+                        placement: None,
+                        eliminations,
+                        cells: cluster,
+                        values: vec![value],
+                        houses: Vec::new(),
+                        cover: Vec::new()
+                    })
+                }
+            }
+        }
+
+        None
+    }
+
+    // Alternating strong and weak links on one value, starting and ending
+    // with a strong link. One of the two ends holds the value.
+    fn x_chain(&self) -> Option<Deduction> {
+        for value in 1..=9 {
+            let links = self.strong_links(value);
+            let places: Vec<usize> = (0..81).filter(|cell| self.masks[*cell] & (1 << value) != 0).collect();
+
+            for &start in &places {
+                // A node is a cell and whether it was reached by a strong link
+                let mut previous: HashMap<(usize, bool), (usize, bool)> = HashMap::new();
+                let mut queue: VecDeque<(usize, bool)> = VecDeque::from([(start, false)]);
+
+                while let Some((cell, strong)) = queue.pop_front() {
+                    let next: Vec<usize> = match strong {
+                        false => links.iter()
+                            .filter_map(|(first, second, _)| match (*first == cell, *second == cell) {
+                                (true, _) => Some(*second),
+                                (_, true) => Some(*first),
+                                _ => None
+                            })
+                            .collect(),
+                        true => places.iter().copied().filter(|other| sees(cell, *other)).collect()
+                    };
+
+                    for other in next {
+                        let node = (other, !strong);
+                        if other == start || previous.contains_key(&node) {
+                            continue;
+                        }
+                        previous.insert(node, (cell, strong));
+                        queue.push_back(node);
+
+                        if strong {
+                            continue;
+                        }
+
+                        let chain = Candidates::walk_back(&previous, node, (start, false));
+                        if chain.len() < 4 || !chain.iter().all_unique() {
+                            continue;
+                        }
+
+                        let eliminations: Vec<(usize, usize)> = self.common_peers(&[start, other], value).into_iter()
+                            .filter(|cell| !chain.contains(cell))
+                            .map(|cell| (cell, value))
+                            .collect();
+
+                        if !eliminations.is_empty() {
+                            return Some(Deduction {
+                                technique: Technique::XChain,
+                                placement: None,
+                                eliminations,
+                                cells: chain,
+                                values: vec![value],
+                                houses: Vec::new(),
+                                cover: Vec::new()
+                            })
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    // A chain of bivalue cells, each one sharing a value with the next. If
+    // the first cell is not X the last cell is X, so one of the ends is X.
+    fn xy_chain(&self) -> Option<Deduction> {
+        let bivalue = self.bivalue_cells();
+
+        for &start in &bivalue {
+            for x in digits(self.masks[start]) {
+                // A node is a cell and the value it cannot be in the chain
+                let mut previous: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+                let mut queue: VecDeque<(usize, usize)> = VecDeque::from([(start, x)]);
+
+                while let Some((cell, excluded)) = queue.pop_front() {
+                    let value = (self.masks[cell] & !(1 << excluded)).trailing_zeros() as usize;
+
+                    for &other in bivalue.iter().filter(|other| sees(cell, **other) && self.masks[**other] & (1 << value) != 0) {
+                        let node = (other, value);
+                        if other == start || previous.contains_key(&node) {
+                            continue;
+                        }
+                        previous.insert(node, (cell, excluded));
+                        queue.push_back(node);
+
+                        let end_value = (self.masks[other] & !(1 << value)).trailing_zeros() as usize;
+                        if end_value != x {
+                            continue;
+                        }
+
+                        let chain = Candidates::walk_back(&previous, node, (start, x));
+                        if chain.len() < 3 || !chain.iter().all_unique() {
+                            continue;
+                        }
+
+                        let eliminations: Vec<(usize, usize)> = self.common_peers(&[start, other], x).into_iter()
+                            .filter(|cell| !chain.contains(cell))
+                            .map(|cell| (cell, x))
+                            .collect();
+
+                        if !eliminations.is_empty() {
+                            return Some(Deduction {
+                                technique: Technique::XYChain,
+                                placement: None,
+                                eliminations,
+                                cells: chain,
+                                values: vec![x],
+                                houses: Vec::new(),
+                                cover: Vec::new()
+                            })
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    // Four cells in two rows, two columns and two blocks that could all be
+    // A or B would allow two solutions. With a unique solution, the extra
+    // candidates of the corners must break the pattern.
+    fn unique_rectangle(&self) -> Option<Deduction> {
+        if !self.unique_solution {
+            return None
+        }
+
+        for (top, bottom) in (0..9).tuple_combinations() {
+            for (left, right) in (0..9).tuple_combinations() {
+                let corners = [top * 9 + left, top * 9 + right, bottom * 9 + left, bottom * 9 + right];
+                let blocks = corners.iter().map(|cell| block_of(*cell)).unique().count();
+                if blocks != 2 {
+                    continue;
+                }
+
+                let common = corners.iter().fold(0b11_1111_1110, |mask, cell| mask & self.masks[*cell]);
+
+                for (a, b) in digits(common).tuple_combinations() {
+                    let deduction = self.rectangle_type(corners, a, b);
+                    if deduction.is_some() {
+                        return deduction
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    fn rectangle_type(&self, corners: [usize; 4], a: usize, b: usize) -> Option<Deduction> {
+        let pair = (1 << a) | (1 << b);
+        let (floor, roof): (Vec<usize>, Vec<usize>) = corners.iter().partition(|cell| self.masks[**cell] == pair);
+
+        let deduction = |technique, eliminations: Vec<(usize, usize)>, values, houses| {
+            (!eliminations.is_empty()).then(|| Deduction {
+                technique,
+                placement: None,
+                eliminations,
+                cells: corners.to_vec(),
+                values,
+                houses,
+                cover: Vec::new()
+            })
+        };
+
+        // Type 1, only one corner has extra candidates so it is neither A nor B
+        if let [corner] = roof[..] {
+            return deduction(Technique::UniqueRectangle1, vec![(corner, a), (corner, b)], vec![a, b], Vec::new())
+        }
+
+        // The other types need a roof of two cells sharing a house
+        let [first, second] = roof[..] else {
+            return None
+        };
+        if floor.len() != 2 || !sees(first, second) {
+            return None
+        }
+        let extra = (self.masks[first] | self.masks[second]) & !pair;
+        let shared: Vec<House> = [House::Row(row_of(first)), House::Col(col_of(first)), House::Block(block_of(first))]
+            .into_iter()
+            .filter(|house| house.cells().contains(&second))
+            .collect();
+
+        // Type 2, both roof cells have the same single extra candidate C, one of them is C
+        if self.masks[first] == self.masks[second] && extra.count_ones() == 1 {
+            let c = extra.trailing_zeros() as usize;
+            let eliminations = self.common_peers(&[first, second], c).into_iter()
+                .map(|cell| (cell, c))
+                .collect();
+
+            if let Some(deduction) = deduction(Technique::UniqueRectangle2, eliminations, vec![a, b, c], Vec::new()) {
+                return Some(deduction)
+            }
+        }
+
+        // Type 3, the extra candidates act as one cell in a naked subset
+        for house in &shared {
+            let others: Vec<usize> = self.unsolved(*house).into_iter()
+                .filter(|cell| *cell != first && *cell != second)
+                .collect();
+
+            for size in 1..=3 {
+                for subset in others.iter().copied().combinations(size) {
+                    let mask = subset.iter().fold(extra, |mask, cell| mask | self.masks[*cell]);
+                    if mask.count_ones() as usize != size + 1 {
+                        continue;
+                    }
+
+                    let eliminations = others.iter()
+                        .filter(|cell| !subset.contains(cell))
+                        .flat_map(|cell| digits(self.masks[*cell] & mask).map(move |value| (*cell, value)))
+                        .collect();
+
+                    let mut values = vec![a, b];
+                    values.extend(digits(mask));
+                    if let Some(deduction) = deduction(Technique::UniqueRectangle3, eliminations, values, vec![*house]) {
+                        return Some(deduction)
+                    }
+                }
+            }
+        }
+
+        // Type 4, A is only possible in the roof within a house, so the roof cannot hold B
+        for house in &shared {
+            for (kept, removed) in [(a, b), (b, a)] {
+                if self.places(*house, kept) != [first, second] {
+                    continue;
+                }
+
+                let eliminations = vec![(first, removed), (second, removed)];
+                if let Some(deduction) = deduction(Technique::UniqueRectangle4, eliminations, vec![kept, removed], vec![*house]) {
+                    return Some(deduction)
+                }
+            }
+        }
+
+        None
+    }
+
+    // Bivalue Universal Grave, when every unsolved cell but one has two
+    // candidates and every candidate appears twice in each house, the grid
+    // would have two solutions without the one value of the remaining cell
+    // that appears three times in its row, column and block
+    fn bug_plus_one(&self) -> Option<Deduction> {
+        if !self.unique_solution {
+            return None
+        }
+
+        let unsolved: Vec<usize> = (0..81).filter(|cell| self.values[*cell] == 0).collect();
+        let mut others = unsolved.iter().filter(|cell| self.masks[**cell].count_ones() != 2);
+
+        let (Some(&cell), None) = (others.next(), others.next()) else {
+            return None
+        };
+        if self.masks[cell].count_ones() != 3 {
+            return None
+        }
+
+        let houses = [House::Row(row_of(cell)), House::Col(col_of(cell)), House::Block(block_of(cell))];
+        for house in House::all() {
+            for value in 1..=9 {
+                match self.places(house, value).len() {
+                    0 | 2 => (),
+                    3 if houses.contains(&house) && self.masks[cell] & (1 << value) != 0 => (),
+                    _ => return None
+                }
+            }
+        }
+
+        let extra: Vec<usize> = digits(self.masks[cell])
+            .filter(|value| houses.iter().all(|house| self.places(*house, *value).len() == 3))
+            .collect();
+        let [value] = extra[..] else {
+            return None
+        };
+
+        Some(Deduction {
+            technique: Technique::BugPlusOne,
+            placement: Some((cell, value)),
+            eliminations: Vec::new(),
+            cells: vec![cell],
+            values: vec![value],
+            houses: houses.to_vec(),
+            cover: Vec::new()
+        })
+    }
+
+    // Cells of a chain found by a breadth first search, from start to end
+    fn walk_back<T: Copy + Eq + std::hash::Hash>(previous: &HashMap<(usize, T), (usize, T)>, end: (usize, T), start: (usize, T)) -> Vec<usize> {
+        let mut chain = vec![end.0];
+        let mut node = end;
+
+        while node != start {
+            node = previous[&node];
+            chain.push(node.0);
+        }
+
+        chain.reverse();
+        chain
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(row: usize, col: usize) -> usize {
+        (row - 1) * 9 + col - 1
+    }
+
+    // An empty grid where the listed cells only have the given candidates
+    fn position(cells: &[((usize, usize), &[usize])]) -> Candidates {
+        let mut candidates = Candidates::new(Shape::classic(), &[]);
+
+        for ((row, col), values) in cells {
+            candidates.masks[cell(*row, *col)] = values.iter().fold(0, |mask, value| mask | 1 << value);
+        }
+
+        candidates
+    }
+
+    #[test]
+    fn cells_of_rectangular_blocks() {
+        let shape = Shape::new(2, 3).unwrap();
+        assert_eq!(House::Block(1).cells_in(shape), [3, 4, 5, 9, 10, 11]);
+        assert_eq!(House::Block(4).cells_in(shape), [24, 25, 26, 30, 31, 32]);
+
+        let shape = Shape::new(3, 4).unwrap();
+        assert_eq!(House::Block(4).cells_in(shape), [40, 41, 42, 43, 52, 53, 54, 55, 64, 65, 66, 67]);
+
+        // Every cell of a block is placed in that block by the shape
+        for shape in [Shape::new(2, 3).unwrap(), Shape::new(3, 4).unwrap()] {
+            for block in 0..shape.size() {
+                let cells = House::Block(block).cells_in(shape);
+                assert_eq!(cells.len(), shape.size());
+                assert!(cells.iter().all(|cell| shape.box_of(*cell) == block));
+            }
+        }
+    }
+
+    // Remove the value from every cell but the listed ones
+    fn keep(candidates: &mut Candidates, value: usize, cells: &[(usize, usize)]) {
+        for other in 0..81 {
+            if !cells.iter().any(|(row, col)| cell(*row, *col) == other) {
+                candidates.eliminate(other, value);
+            }
+        }
+    }
+
+    // Eliminations of each of the values from each of the cells
+    fn each(cells: &[(usize, usize)], values: std::ops::RangeInclusive<usize>) -> Vec<(usize, usize)> {
+        cells.iter().flat_map(|(row, col)| values.clone().map(move |value| (cell(*row, *col), value))).collect()
+    }
+
+    #[test]
+    fn naked_pair() {
+        let candidates = position(&[((1, 1), &[1, 2]), ((1, 5), &[1, 2])]);
+        let deduction = candidates.naked_subset(2).unwrap();
+
+        assert_eq!(deduction.technique, Technique::NakedPair);
+        assert_eq!(deduction.cells, [cell(1, 1), cell(1, 5)]);
+        assert_eq!(deduction.houses, [House::Row(0)]);
+        assert_eq!(deduction.eliminations, each(&[(1, 2), (1, 3), (1, 4), (1, 6), (1, 7), (1, 8), (1, 9)], 1..=2));
+    }
+
+    #[test]
+    fn naked_quad() {
+        // No cell holds all four values, and the block pairs are too few for a quad
+        let candidates = position(&[((5, 1), &[1, 2]), ((5, 3), &[2, 3]), ((5, 7), &[3, 4]), ((5, 9), &[1, 4])]);
+        let deduction = candidates.naked_subset(4).unwrap();
+
+        assert_eq!(deduction.technique, Technique::NakedQuad);
+        assert_eq!(deduction.values, [1, 2, 3, 4]);
+        assert_eq!(deduction.houses, [House::Row(4)]);
+        assert_eq!(deduction.eliminations, each(&[(5, 2), (5, 4), (5, 5), (5, 6), (5, 8)], 1..=4));
+    }
+
+    #[test]
+    fn hidden_pair() {
+        let mut candidates = position(&[]);
+        keep(&mut candidates, 1, &[(1, 1), (2, 2)]);
+        keep(&mut candidates, 2, &[(1, 1), (2, 2)]);
+        let deduction = candidates.hidden_subset(2).unwrap();
+
+        assert_eq!(deduction.technique, Technique::HiddenPair);
+        assert_eq!(deduction.cells, [cell(1, 1), cell(2, 2)]);
+        assert_eq!(deduction.houses, [House::Block(0)]);
+        assert_eq!(deduction.eliminations, each(&[(1, 1), (2, 2)], 3..=9));
+    }
+
+    #[test]
+    fn hidden_quad() {
+        let mut candidates = position(&[]);
+        for value in 1..=4 {
+            keep(&mut candidates, value, &[(1, 1), (1, 2), (2, 1), (2, 2)]);
+        }
+        let deduction = candidates.hidden_subset(4).unwrap();
+
+        assert_eq!(deduction.technique, Technique::HiddenQuad);
+        assert_eq!(deduction.values, [1, 2, 3, 4]);
+        assert_eq!(deduction.houses, [House::Block(0)]);
+        assert_eq!(deduction.eliminations, each(&[(1, 1), (1, 2), (2, 1), (2, 2)], 5..=9));
+    }
+
+    // Remove the value from every cell of the house but the listed ones
+    fn limit(candidates: &mut Candidates, value: usize, house: House, cells: &[(usize, usize)]) {
+        for other in house.cells() {
+            if !cells.iter().any(|(row, col)| cell(*row, *col) == other) {
+                candidates.eliminate(other, value);
+            }
+        }
+    }
+
+    #[test]
+    fn pointing() {
+        let mut candidates = position(&[]);
+        limit(&mut candidates, 1, House::Block(0), &[(1, 1), (1, 2)]);
+        let deduction = candidates.pointing().unwrap();
+
+        assert_eq!(deduction.cells, [cell(1, 1), cell(1, 2)]);
+        assert_eq!(deduction.houses, [House::Block(0), House::Row(0)]);
+        assert_eq!(deduction.eliminations, each(&[(1, 4), (1, 5), (1, 6), (1, 7), (1, 8), (1, 9)], 1..=1));
+    }
+
+    #[test]
+    fn claiming() {
+        let mut candidates = position(&[]);
+        limit(&mut candidates, 1, House::Row(0), &[(1, 1), (1, 2)]);
+        let deduction = candidates.claiming().unwrap();
+
+        assert_eq!(deduction.cells, [cell(1, 1), cell(1, 2)]);
+        assert_eq!(deduction.houses, [House::Row(0), House::Block(0)]);
+        assert_eq!(deduction.eliminations, each(&[(2, 1), (2, 2), (2, 3), (3, 1), (3, 2), (3, 3)], 1..=1));
+    }
+
+    #[test]
+    fn x_wing() {
+        let mut candidates = position(&[]);
+        limit(&mut candidates, 1, House::Row(0), &[(1, 2), (1, 8)]);
+        limit(&mut candidates, 1, House::Row(4), &[(5, 2), (5, 8)]);
+        let deduction = candidates.fish(2).unwrap();
+
+        assert_eq!(deduction.technique, Technique::XWing);
+        assert_eq!(deduction.cells, [cell(1, 2), cell(1, 8), cell(5, 2), cell(5, 8)]);
+        assert_eq!(deduction.houses, [House::Row(0), House::Row(4)]);
+        assert_eq!(deduction.cover, [House::Col(1), House::Col(7)]);
+        assert_eq!(deduction.eliminations, each(&[
+            (2, 2), (3, 2), (4, 2), (6, 2), (7, 2), (8, 2), (9, 2),
+            (2, 8), (3, 8), (4, 8), (6, 8), (7, 8), (8, 8), (9, 8)
+        ], 1..=1));
+    }
+
+    #[test]
+    fn swordfish() {
+        // No two of the rows share both columns, so there is no X-Wing
+        let mut candidates = position(&[]);
+        limit(&mut candidates, 1, House::Row(0), &[(1, 1), (1, 4)]);
+        limit(&mut candidates, 1, House::Row(4), &[(5, 4), (5, 7)]);
+        limit(&mut candidates, 1, House::Row(8), &[(9, 1), (9, 7)]);
+        assert_eq!(candidates.fish(2), None);
+        let deduction = candidates.fish(3).unwrap();
+
+        assert_eq!(deduction.technique, Technique::Swordfish);
+        assert_eq!(deduction.cells, [cell(1, 1), cell(1, 4), cell(5, 4), cell(5, 7), cell(9, 1), cell(9, 7)]);
+        assert_eq!(deduction.houses, [House::Row(0), House::Row(4), House::Row(8)]);
+        assert_eq!(deduction.cover, [House::Col(0), House::Col(3), House::Col(6)]);
+        assert_eq!(deduction.eliminations, each(&[
+            (2, 1), (3, 1), (4, 1), (6, 1), (7, 1), (8, 1),
+            (2, 4), (3, 4), (4, 4), (6, 4), (7, 4), (8, 4),
+            (2, 7), (3, 7), (4, 7), (6, 7), (7, 7), (8, 7)
+        ], 1..=1));
+    }
+
+    #[test]
+    fn jellyfish() {
+        // Based on columns, every row still holds too many places
+        let mut candidates = position(&[]);
+        limit(&mut candidates, 1, House::Col(0), &[(1, 1), (2, 1)]);
+        limit(&mut candidates, 1, House::Col(2), &[(2, 3), (3, 3)]);
+        limit(&mut candidates, 1, House::Col(4), &[(3, 5), (4, 5)]);
+        limit(&mut candidates, 1, House::Col(6), &[(4, 7), (1, 7)]);
+        assert_eq!(candidates.fish(3), None);
+        let deduction = candidates.fish(4).unwrap();
+
+        assert_eq!(deduction.technique, Technique::Jellyfish);
+        assert_eq!(deduction.cells, [cell(1, 1), cell(1, 7), cell(2, 1), cell(2, 3), cell(3, 3), cell(3, 5), cell(4, 5), cell(4, 7)]);
+        assert_eq!(deduction.houses, [House::Col(0), House::Col(2), House::Col(4), House::Col(6)]);
+        assert_eq!(deduction.cover, [House::Row(0), House::Row(1), House::Row(2), House::Row(3)]);
+        assert_eq!(deduction.eliminations, each(&[
+            (1, 2), (1, 4), (1, 6), (1, 8), (1, 9),
+            (2, 2), (2, 4), (2, 6), (2, 8), (2, 9),
+            (3, 2), (3, 4), (3, 6), (3, 8), (3, 9),
+            (4, 2), (4, 4), (4, 6), (4, 8), (4, 9)
+        ], 1..=1));
+    }
+
+    #[test]
+    fn xy_wing() {
+        let candidates = position(&[((1, 1), &[1, 2]), ((1, 5), &[1, 3]), ((3, 2), &[2, 3])]);
+        let deduction = candidates.xy_wing().unwrap();
+
+        assert_eq!(deduction.cells, [cell(1, 1), cell(1, 5), cell(3, 2)]);
+        assert_eq!(deduction.eliminations, [(cell(1, 2), 3), (cell(1, 3), 3), (cell(3, 4), 3), (cell(3, 5), 3), (cell(3, 6), 3)]);
+    }
+
+    #[test]
+    fn xyz_wing() {
+        let candidates = position(&[((1, 1), &[1, 2, 3]), ((1, 5), &[1, 3]), ((2, 2), &[2, 3])]);
+        let deduction = candidates.xyz_wing().unwrap();
+
+        assert_eq!(deduction.cells, [cell(1, 1), cell(1, 5), cell(2, 2)]);
+        assert_eq!(deduction.eliminations, [(cell(1, 2), 3), (cell(1, 3), 3)]);
+    }
+
+    #[test]
+    fn w_wing() {
+        // 1 is only in r9c2 and r9c8 in row 9, each sees one of the pair
+        let mut candidates = position(&[((1, 2), &[1, 2]), ((5, 8), &[1, 2])]);
+        keep(&mut candidates, 1, &[(1, 2), (5, 8), (9, 2), (9, 8)]);
+        let deduction = candidates.w_wing().unwrap();
+
+        assert_eq!(deduction.cells, [cell(1, 2), cell(9, 2), cell(9, 8), cell(5, 8)]);
+        assert_eq!(deduction.eliminations, [(cell(1, 8), 2), (cell(5, 2), 2)]);
+    }
+
+    // Strong links on 1 from r1c1 to r1c7, r3c9 and r9c9. Row 9 and column 1
+    // hold a third 1, so r9c1 sees both ends without being linked to them.
+    fn chain_position() -> Candidates {
+        let mut candidates = position(&[]);
+        keep(&mut candidates, 1, &[(1, 1), (1, 7), (3, 9), (9, 9), (5, 1), (9, 1), (9, 5)]);
+        candidates
+    }
+
+    #[test]
+    fn simple_coloring() {
+        let deduction = chain_position().simple_coloring().unwrap();
+
+        assert_eq!(deduction.cells, [cell(1, 1), cell(1, 7), cell(3, 9), cell(9, 9)]);
+        assert_eq!(deduction.eliminations, [(cell(9, 1), 1)]);
+    }
+
+    #[test]
+    fn x_chain() {
+        let deduction = chain_position().x_chain().unwrap();
+
+        assert_eq!(deduction.cells, [cell(1, 1), cell(1, 7), cell(3, 9), cell(9, 9)]);
+        assert_eq!(deduction.eliminations, [(cell(9, 1), 1)]);
+    }
+
+    #[test]
+    fn xy_chain() {
+        let candidates = position(&[((1, 1), &[1, 2]), ((1, 5), &[2, 3]), ((5, 5), &[3, 4]), ((5, 9), &[1, 4])]);
+        let deduction = candidates.xy_chain().unwrap();
+
+        assert_eq!(deduction.cells, [cell(1, 1), cell(1, 5), cell(5, 5), cell(5, 9)]);
+        assert_eq!(deduction.eliminations, [(cell(1, 9), 1), (cell(5, 1), 1)]);
+    }
+
+    // The corners r1c1, r1c4, r2c1 and r2c4, the roof in row 2
+    fn rectangle(first: &[usize], second: &[usize]) -> Candidates {
+        let mut candidates = position(&[((1, 1), &[1, 2]), ((1, 4), &[1, 2]), ((2, 1), first), ((2, 4), second)]);
+        candidates.set_unique_solution(true);
+        candidates
+    }
+
+    #[test]
+    fn unique_rectangle_type_1() {
+        let deduction = rectangle(&[1, 2], &[1, 2, 3]).unique_rectangle().unwrap();
+
+        assert_eq!(deduction.technique, Technique::UniqueRectangle1);
+        assert_eq!(deduction.eliminations, [(cell(2, 4), 1), (cell(2, 4), 2)]);
+    }
+
+    #[test]
+    fn unique_rectangle_needs_unique_solution() {
+        let mut candidates = rectangle(&[1, 2], &[1, 2, 3]);
+        candidates.set_unique_solution(false);
+
+        assert_eq!(candidates.unique_rectangle(), None);
+    }
+
+    #[test]
+    fn unique_rectangle_type_2() {
+        let deduction = rectangle(&[1, 2, 3], &[1, 2, 3]).unique_rectangle().unwrap();
+
+        assert_eq!(deduction.technique, Technique::UniqueRectangle2);
+        assert_eq!(deduction.eliminations, [2, 3, 5, 6, 7, 8, 9].map(|col| (cell(2, col), 3)));
+    }
+
+    #[test]
+    fn unique_rectangle_type_3() {
+        // The extra 3 and 4 of the roof form a naked pair with r2c9
+        let mut candidates = rectangle(&[1, 2, 3], &[1, 2, 4]);
+        for col in [2, 3, 6, 7, 8] {
+            candidates.masks[cell(2, col)] = 0b11_1110_0000;
+        }
+        candidates.masks[cell(2, 5)] = (1 << 3) | (1 << 5) | (1 << 6);
+        candidates.masks[cell(2, 9)] = (1 << 3) | (1 << 4);
+        let deduction = candidates.unique_rectangle().unwrap();
+
+        assert_eq!(deduction.technique, Technique::UniqueRectangle3);
+        assert_eq!(deduction.eliminations, [(cell(2, 5), 3)]);
+    }
+
+    #[test]
+    fn unique_rectangle_type_4() {
+        // 1 is only in the roof within row 2, so the roof cannot be 2
+        let mut candidates = rectangle(&[1, 2, 3], &[1, 2, 4]);
+        for col in [2, 3, 5, 6, 7, 8, 9] {
+            candidates.eliminate(cell(2, col), 1);
+        }
+        let deduction = candidates.unique_rectangle().unwrap();
+
+        assert_eq!(deduction.technique, Technique::UniqueRectangle4);
+        assert_eq!(deduction.eliminations, [(cell(2, 1), 2), (cell(2, 4), 2)]);
+    }
+
+    // Reached while solving the puzzle of seed 724, r2c4 is the only cell with three candidates
+    fn bug_position() -> Candidates {
+        let values = "4.8.517.3.51.7.4.82734986518.254.1.7735186942.14.278.5129834576346715289587.6.314";
+        let grid: Vec<usize> = values.chars().map(|symbol| symbol.to_digit(10).unwrap_or(0) as usize).collect();
+        let mut candidates = Candidates::new(Shape::classic(), &grid);
+        let unsolved: [((usize, usize), &[usize]); 15] = [
+            ((1, 2), &[6, 9]), ((1, 4), &[2, 6]), ((1, 8), &[2, 9]),
+            ((2, 1), &[6, 9]), ((2, 4), &[2, 3, 6]), ((2, 6), &[2, 3]), ((2, 8), &[2, 9]),
+            ((4, 2), &[6, 9]), ((4, 6), &[3, 9]), ((4, 8), &[3, 6]),
+            ((6, 1), &[6, 9]), ((6, 4), &[3, 9]), ((6, 8), &[3, 6]),
+            ((9, 4), &[2, 9]), ((9, 6), &[2, 9])
+        ];
+        for ((row, col), values) in unsolved {
+            candidates.masks[cell(row, col)] = values.iter().fold(0, |mask, value| mask | 1 << value);
+        }
+        candidates.set_unique_solution(true);
+        candidates
+    }
+
+    #[test]
+    fn bug_plus_one() {
+        let deduction = bug_position().bug_plus_one().unwrap();
+
+        assert_eq!(deduction.placement, Some((cell(2, 4), 2)));
+        assert_eq!(deduction.houses, [House::Row(1), House::Col(3), House::Block(1)]);
+    }
+
+    #[test]
+    fn bug_plus_one_needs_every_value_twice() {
+        // 9 is left once in row 9, the grid is no grave with two solutions
+        let mut candidates = bug_position();
+        candidates.masks[cell(9, 6)] = (1 << 2) | (1 << 3);
+
+        assert_eq!(candidates.bug_plus_one(), None);
+    }
+}
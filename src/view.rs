@@ -0,0 +1,93 @@
+use std::{collections::HashSet, fmt::Display};
+
+use colored::{ColoredString, Colorize};
+
+use crate::cursor::Cursor;
+use crate::model::Sudoko;
+use crate::shape;
+
+//  Draws the board, the cell under the cursor is highlighted, conflicting
+//  cells are red and the locked givens are underlined. Selection is only
+//  known here, not in the model.
+pub struct SudokoView<'a> {
+    sudoko: &'a Sudoko,
+    cursor: Option<Cursor>
+}
+
+impl<'a> SudokoView<'a> {
+    pub fn new(sudoko: &'a Sudoko, cursor: Option<Cursor>) -> SudokoView<'a> {
+        SudokoView { sudoko, cursor }
+    }
+
+    fn cell(&self, row: usize, col: usize, conflicting: &HashSet<Cursor>) -> ColoredString {
+        let value = self.sudoko.get_value(row, col).unwrap();
+        let cursor = Cursor { row, col };
+        let selected = self.cursor == Some(cursor);
+
+        let styled = match (value.to_value().map(shape::symbol), value.color(), selected) {
+            (Some(digit), _, false) if conflicting.contains(&cursor) => digit.to_string().bright_red().bold(),
+            (Some(digit), _, true) if conflicting.contains(&cursor) => digit.to_string().bright_red().bold().on_white(),
+            (Some(digit), Some(color), false) => digit.to_string().color(color).dimmed(),
+            (Some(digit), Some(color), true) => digit.to_string().color(color).bold(),
+            (_, _, false) => " ".normal(),
+            (_, _, true) => " ".on_white()
+        };
+
+        match self.sudoko.is_locked(row, col) {
+            false => styled,
+            true => styled.underline()
+        }
+    }
+
+    // Horizontal line made of the left corner, the line, the crossings at the
+    // box and cell boundaries and the right corner
+    fn border(&self, f: &mut std::fmt::Formatter<'_>, [left, line, box_cross, cell_cross, right]: [char; 5]) -> std::fmt::Result {
+        let shape = self.sudoko.shape();
+
+        write!(f, "{left}")?;
+        for col in 1..=shape.size() {
+            write!(f, "{0}{0}{0}", line)?;
+
+            match col {
+                _ if col == shape.size() => write!(f, "{right}")?,
+                _ if col % shape.box_cols() == 0 => write!(f, "{box_cross}")?,
+                _ => write!(f, "{cell_cross}")?
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for SudokoView<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+
+        // Every cell taking part in a conflict is drawn in red
+        let conflicting: HashSet<Cursor> = self.sudoko.conflicts()
+            .into_iter()
+            .flat_map(|conflict| conflict.cells)
+            .collect();
+
+        let shape = self.sudoko.shape();
+        let size = shape.size();
+
+        for row in 1..=size {
+            match row {
+                1 => self.border(f, ['┏', '━', '┳', '┯', '┓'])?,
+                _ if (row - 1) % shape.box_rows() == 0 => self.border(f, ['┣', '━', '╋', '┿', '┫'])?,
+                _ => self.border(f, ['┠', '─', '╂', '┼', '┨'])?
+            }
+            writeln!(f)?;
+
+            for col in 1..=size {
+                match (col - 1) % shape.box_cols() {
+                    0 => write!(f, "┃ {} ", self.cell(row, col, &conflicting))?,
+                    _ => write!(f, "│ {} ", self.cell(row, col, &conflicting))?
+                }
+            }
+            writeln!(f, "┃")?;
+        }
+
+        self.border(f, ['┗', '━', '┻', '┷', '┛'])
+    }
+}